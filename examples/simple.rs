@@ -3,54 +3,13 @@
 use std::fmt::Debug;
 
 use seal::pair::{
-    Alignment, AlignmentSet, InMemoryAlignmentMatrix, NeedlemanWunsch, SmithWaterman, Step,
+    AlignmentFormatter, AlignmentSet, InMemoryAlignmentMatrix, NeedlemanWunsch, SmithWaterman,
     Strategy,
 };
 
-fn trace(x_seq: &[char], y_seq: &[char], alignment: &Alignment) {
-    let mut x_vec: Vec<char> = vec![];
-    let mut y_vec: Vec<char> = vec![];
-    for step in alignment.steps() {
-        match step {
-            Step::Align { x, y } => {
-                print!("=");
-                x_vec.push(x_seq[x]);
-                y_vec.push(y_seq[y]);
-            }
-            Step::Delete { x } => {
-                print!(">");
-                x_vec.push(x_seq[x]);
-                y_vec.push('-');
-            }
-            Step::Insert { y } => {
-                print!("<");
-                x_vec.push('-');
-                y_vec.push(y_seq[y]);
-            }
-        }
-    }
-
-    println!();
-
-    let x_str: String = x_vec.into_iter().collect();
-    let y_str: String = y_vec.into_iter().collect();
-
-    for (x, y) in x_str.chars().zip(y_str.chars()) {
-        if x == y {
-            print!("=");
-        } else {
-            print!("|");
-        }
-    }
-    println!();
-
-    println!("{}", x_str);
-    println!("{}", y_str);
-}
-
-fn align<S: Strategy>(str_x: &str, str_y: &str, strategy: S)
+fn align<S>(str_x: &str, str_y: &str, strategy: S)
 where
-    S: Debug,
+    S: Strategy<Score = isize> + Debug,
 {
     let sequence_x: Vec<char> = str_x.chars().collect();
     let sequence_y: Vec<char> = str_y.chars().collect();
@@ -59,15 +18,23 @@ where
             sequence_x[x] == sequence_y[y]
         });
 
+    let formatter = AlignmentFormatter::new();
+
     match alignment_set {
         Ok(alignment_set) => {
             let local_alignment = alignment_set.local_alignment();
             println!("Local alignment:");
-            trace(&sequence_x, &sequence_y, &local_alignment);
+            print!(
+                "{}",
+                formatter.format(&local_alignment, &sequence_x, &sequence_y)
+            );
             println!();
             let global_alignment = alignment_set.global_alignment();
             println!("Global alignment:");
-            trace(&sequence_x, &sequence_y, &global_alignment);
+            print!(
+                "{}",
+                formatter.format(&global_alignment, &sequence_x, &sequence_y)
+            );
             println!("\n--------------------------\n");
         }
         Err(error) => {
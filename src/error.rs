@@ -0,0 +1,78 @@
+use std::{error, fmt, io};
+
+use crate::pair::cursor::Cursor;
+use crate::pair::step_mask::StepMask;
+
+/// The error type of fallible alignment operations, shared by all `AlignmentMatrix` backends.
+#[derive(Debug)]
+pub enum Error {
+    /// A matrix of `width * height` cells whose size overflows `usize`.
+    SizeOverflow { width: usize, height: usize },
+    /// An empty sequence, whose alignments are trivial.
+    EmptySequence,
+    /// A matrix whose buffer of `cells` cells couldn't be allocated.
+    Allocation { cells: usize },
+    /// A step mask not being a single step, or leading off the matrix from `cursor`.
+    InvalidStep { cursor: Cursor, step_mask: StepMask },
+    /// A traceback not yielding any alignment.
+    NoAlignment,
+    /// A persisted matrix file with a missing or inconsistent header.
+    InvalidHeader(&'static str),
+    /// A persisted alignment set computed with different strategy parameters or score type.
+    StrategyMismatch,
+    /// A strategy not supported by an algorithm, for the given reason.
+    UnsupportedStrategy(&'static str),
+    /// A backend not supporting the requested alignment.
+    UnsupportedBackend,
+    /// A `width * height` matrix not fitting into an adaptive backend's budget.
+    BudgetExceeded { width: usize, height: usize },
+    /// An I/O error of a memory-mapped matrix.
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, form: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::SizeOverflow { width, height } => {
+                write!(form, "matrix size {} * {} overflows", width, height)
+            }
+            Error::EmptySequence => write!(form, "empty sequence"),
+            Error::Allocation { cells } => {
+                write!(form, "failed to allocate matrix of {} cells", cells)
+            }
+            Error::InvalidStep { cursor, step_mask } => write!(
+                form,
+                "invalid step {:?} at ({}, {})",
+                step_mask, cursor.x, cursor.y
+            ),
+            Error::NoAlignment => write!(form, "no alignment found"),
+            Error::InvalidHeader(reason) => write!(form, "invalid matrix header: {}", reason),
+            Error::StrategyMismatch => write!(form, "mismatching strategy"),
+            Error::UnsupportedStrategy(reason) => write!(form, "unsupported strategy: {}", reason),
+            Error::UnsupportedBackend => write!(form, "unsupported backend"),
+            Error::BudgetExceeded { width, height } => {
+                write!(
+                    form,
+                    "matrix of {} * {} cells exceeds budget",
+                    width, height
+                )
+            }
+            Error::Io(error) => write!(form, "I/O error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(error)
+    }
+}
@@ -1,7 +1,10 @@
 // pub mod basic_scoring;
 
+pub mod error;
 pub mod pair;
 
+pub use self::error::Error;
+
 #[test]
 fn it_works() {
     // let basic_scoring = basic_scoring::BasicScoring::new(0, 1, 1);
@@ -0,0 +1,399 @@
+use std::fmt::Display;
+
+#[cfg(feature = "graphemes")]
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::error::Error;
+use crate::pair::alignment::Alignment;
+use crate::pair::alignment_matrix::{
+    banded::AlignmentMatrix as BandedAlignmentMatrix,
+    cells,
+    in_memory::AlignmentMatrix as InMemoryAlignmentMatrix,
+    memory_mapped::{self, AlignmentMatrix as MemoryMappedAlignmentMatrix},
+    packed::AlignmentMatrix as PackedAlignmentMatrix,
+    AlignmentMatrix,
+};
+use crate::pair::alignment_set::AlignmentSet;
+use crate::pair::band::Band;
+use crate::pair::end_gaps::EndGaps;
+use crate::pair::formatter::AlignmentFormatter;
+use crate::pair::hirschberg::Hirschberg;
+use crate::pair::score::Score;
+use crate::pair::semi_global::SemiGlobal;
+use crate::pair::statistics::Statistics;
+use crate::pair::strategy::Strategy;
+
+/// Which optimal alignment an `Aligner` returns.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// The alignment with the best global score.
+    Global,
+    /// The alignment with the best local score.
+    Local,
+    /// The alignment with the best global score, with the given end gaps being free.
+    SemiGlobal(EndGaps),
+}
+
+/// Which `AlignmentMatrix` an `Aligner` stores its tracebacks in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Backend {
+    InMemory,
+    /// A `PackedAlignmentMatrix`, using 3 bits per cell for linear
+    /// and 7 bits per cell for affine gap scores.
+    Packed,
+    MemoryMapped,
+    Banded(Band),
+    /// No matrix at all, using `Hirschberg` instead,
+    /// which only supports `Mode::Global` with global strategies and linear gap scores.
+    LinearSpace,
+    /// The first of `InMemory`, `Packed`, `MemoryMapped` and `LinearSpace`
+    /// whose matrix fits into the budget.
+    Adaptive(Budget),
+}
+
+/// The number of bytes `Backend::Adaptive` may use for a matrix in memory and on disk.
+///
+/// The disk budget defaults to zero, i.e. to never picking `Backend::MemoryMapped`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Budget {
+    pub memory: usize,
+    pub disk: usize,
+}
+
+impl Budget {
+    pub fn new(memory: usize) -> Budget {
+        Budget { memory, disk: 0 }
+    }
+
+    pub fn disk(mut self, disk: usize) -> Budget {
+        self.disk = disk;
+        self
+    }
+}
+
+/// Aligns pairs of sequences of symbols, comparing them by equality.
+///
+/// Defaults to `Mode::Global` and `Backend::InMemory`.
+#[derive(Clone, Debug)]
+pub struct Aligner<S> {
+    strategy: S,
+    mode: Mode,
+    backend: Backend,
+}
+
+impl<S> Aligner<S>
+where
+    S: Strategy + Clone,
+{
+    pub fn new(strategy: S) -> Aligner<S> {
+        Aligner {
+            strategy,
+            mode: Mode::Global,
+            backend: Backend::InMemory,
+        }
+    }
+
+    pub fn mode(mut self, mode: Mode) -> Aligner<S> {
+        self.mode = mode;
+        self
+    }
+
+    pub fn backend(mut self, backend: Backend) -> Aligner<S> {
+        self.backend = backend;
+        self
+    }
+
+    /// The backend used for aligning sequences of length `x_len` and `y_len`,
+    /// resolving `Backend::Adaptive` to the backend it picks.
+    pub fn backend_for(&self, x_len: usize, y_len: usize) -> Result<Backend, Error> {
+        let budget = match self.backend {
+            Backend::LinearSpace if !self.supports_linear_space() => {
+                return Err(Error::UnsupportedBackend);
+            }
+            Backend::Adaptive(budget) => budget,
+            backend => return Ok(backend),
+        };
+        let (width, height) = (x_len.saturating_add(1), y_len.saturating_add(1));
+        let fits = |bytes: Result<usize, Error>, budget: usize| matches!(bytes, Ok(bytes) if bytes <= budget);
+        let packed = if self.strategy.has_affine_gaps() {
+            PackedAlignmentMatrix::<Vec<u8>, 7>::bytes(width, height)
+        } else {
+            PackedAlignmentMatrix::<Vec<u8>, 3>::bytes(width, height)
+        };
+        let mapped =
+            cells(width, height).map(|cells| cells.saturating_add(memory_mapped::HEADER_LEN));
+        if fits(cells(width, height), budget.memory) {
+            Ok(Backend::InMemory)
+        } else if fits(packed, budget.memory) {
+            Ok(Backend::Packed)
+        } else if fits(mapped, budget.disk) {
+            Ok(Backend::MemoryMapped)
+        } else if self.supports_linear_space() {
+            Ok(Backend::LinearSpace)
+        } else {
+            Err(Error::BudgetExceeded { width, height })
+        }
+    }
+
+    /// Aligns `x` and `y`, failing only if the matrix can't be created
+    /// or the backend isn't applicable.
+    pub fn align<T>(&self, x: &[T], y: &[T]) -> Result<PairwiseAlignment<T, S::Score>, Error>
+    where
+        T: PartialEq + Clone,
+    {
+        let f = |i: usize, j: usize| x[i] == y[j];
+        let (width, height) = (x.len() + 1, y.len() + 1);
+        let backend = self.backend_for(x.len(), y.len())?;
+        let affine = self.strategy.has_affine_gaps();
+        let alignment = match backend {
+            Backend::InMemory => {
+                let matrix = InMemoryAlignmentMatrix::new(width, height)?;
+                self.alignment(matrix, f)
+            }
+            Backend::Packed if affine => {
+                let matrix = PackedAlignmentMatrix::<Vec<u8>, 7>::new(width, height)?;
+                self.alignment(matrix, f)
+            }
+            Backend::Packed => {
+                let matrix = PackedAlignmentMatrix::<Vec<u8>, 3>::new(width, height)?;
+                self.alignment(matrix, f)
+            }
+            Backend::MemoryMapped => {
+                let matrix = MemoryMappedAlignmentMatrix::new(width, height)?;
+                self.alignment(matrix, f)
+            }
+            Backend::Banded(band) => {
+                let matrix = BandedAlignmentMatrix::with_band(width, height, band);
+                self.alignment(matrix, f)
+            }
+            Backend::LinearSpace => {
+                let hirschberg = Hirschberg::try_new(self.strategy.clone())
+                    .map_err(|_| Error::UnsupportedBackend)?;
+                hirschberg.global_alignment(x.len(), y.len(), f)
+            }
+            Backend::Adaptive(_) => unreachable!(),
+        };
+        Ok(PairwiseAlignment {
+            alignment,
+            backend,
+            x: x.to_vec(),
+            y: y.to_vec(),
+        })
+    }
+
+    /// Aligns `x` and `y` by their `char`s.
+    pub fn align_chars(
+        &self,
+        x: &str,
+        y: &str,
+    ) -> Result<PairwiseAlignment<char, S::Score>, Error> {
+        let x: Vec<char> = x.chars().collect();
+        let y: Vec<char> = y.chars().collect();
+        self.align(&x, &y)
+    }
+
+    /// Aligns `x` and `y` by their bytes.
+    pub fn align_bytes(&self, x: &str, y: &str) -> Result<PairwiseAlignment<u8, S::Score>, Error> {
+        self.align(x.as_bytes(), y.as_bytes())
+    }
+
+    /// Aligns `x` and `y` by their extended grapheme clusters.
+    #[cfg(feature = "graphemes")]
+    pub fn align_graphemes(
+        &self,
+        x: &str,
+        y: &str,
+    ) -> Result<PairwiseAlignment<String, S::Score>, Error> {
+        let x: Vec<String> = x.graphemes(true).map(String::from).collect();
+        let y: Vec<String> = y.graphemes(true).map(String::from).collect();
+        self.align(&x, &y)
+    }
+
+    fn supports_linear_space(&self) -> bool {
+        (self.mode == Mode::Global)
+            && !self.strategy.is_local()
+            && !self.strategy.has_affine_gaps()
+            && self.strategy.free_end_gaps().is_empty()
+    }
+
+    fn alignment<M, F>(&self, matrix: M, f: F) -> Alignment<S::Score>
+    where
+        M: AlignmentMatrix,
+        F: Fn(usize, usize) -> bool,
+    {
+        let strategy = self.strategy.clone();
+        match self.mode {
+            Mode::Global => AlignmentSet::with_matrix(matrix, strategy, f).global_alignment(),
+            Mode::Local => AlignmentSet::with_matrix(matrix, strategy, f).local_alignment(),
+            Mode::SemiGlobal(end_gaps) => {
+                let strategy = SemiGlobal::new(strategy, end_gaps);
+                AlignmentSet::with_matrix(matrix, strategy, f).global_alignment()
+            }
+        }
+    }
+}
+
+/// An alignment together with the sequences it aligns.
+#[derive(Debug)]
+pub struct PairwiseAlignment<T, N = isize> {
+    alignment: Alignment<N>,
+    backend: Backend,
+    x: Vec<T>,
+    y: Vec<T>,
+}
+
+impl<T, N> PairwiseAlignment<T, N>
+where
+    T: PartialEq,
+    N: Score,
+{
+    pub fn alignment(&self) -> &Alignment<N> {
+        &self.alignment
+    }
+
+    pub fn into_alignment(self) -> Alignment<N> {
+        self.alignment
+    }
+
+    pub fn score(&self) -> N {
+        self.alignment.score()
+    }
+
+    /// The backend the alignment was computed with, never being `Backend::Adaptive`.
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    pub fn x(&self) -> &[T] {
+        &self.x
+    }
+
+    pub fn y(&self) -> &[T] {
+        &self.y
+    }
+
+    pub fn statistics(&self) -> Statistics {
+        let (x, y) = (&self.x, &self.y);
+        Statistics::new(&self.alignment, x.len(), y.len(), |i, j| x[i] == y[j])
+    }
+}
+
+impl<T, N> PairwiseAlignment<T, N>
+where
+    T: Copy + Into<char> + PartialEq,
+    N: Score + Display,
+{
+    pub fn format(&self, formatter: &AlignmentFormatter) -> String {
+        formatter.format(&self.alignment, &self.x, &self.y)
+    }
+}
+
+impl<N> PairwiseAlignment<String, N>
+where
+    N: Score + Display,
+{
+    pub fn format_strs(&self, formatter: &AlignmentFormatter) -> String {
+        formatter.format_strs(&self.alignment, &self.x, &self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{NeedlemanWunsch, SmithWaterman};
+
+    #[test]
+    fn aligner_works() {
+        let aligner = Aligner::new(NeedlemanWunsch::new(1, -1, -1, -1));
+        let result = aligner.align_chars("GATTACA", "GCATGCU").unwrap();
+        assert_eq!(result.score(), 0);
+        assert_eq!(result.x().len(), 7);
+
+        let banded = aligner
+            .clone()
+            .backend(Backend::Banded(Band::main_diagonal(3)));
+        let mapped = aligner.clone().backend(Backend::MemoryMapped);
+        for aligner in [banded, mapped].iter() {
+            let other = aligner.align_bytes("GATTACA", "GCATGCU").unwrap();
+            assert_eq!(other.score(), result.score());
+        }
+
+        let local = Aligner::new(SmithWaterman::new(2, -1, -1, -1)).mode(Mode::Local);
+        let result = local.align(&[1, 2, 3, 4, 5], &[9, 3, 4, 5, 9]).unwrap();
+        assert_eq!(result.score(), 6);
+        assert_eq!(result.alignment().x_range(), 2..5);
+        assert_eq!(result.statistics().identities(), 3);
+
+        let end_gaps = EndGaps::X_LEADING | EndGaps::X_TRAILING;
+        let semi_global = aligner.mode(Mode::SemiGlobal(end_gaps));
+        let result = semi_global.align_chars("AAAGATTACAAAA", "GATTACA").unwrap();
+        assert_eq!(result.score(), 7);
+        let formatter = AlignmentFormatter::new().show_score(false);
+        assert_eq!(
+            result.format(&formatter),
+            "x  4 GATTACA 10\n     |||||||\ny  1 GATTACA 7\n"
+        );
+    }
+
+    #[test]
+    fn linear_space_requires_global_strategy() {
+        let aligner = Aligner::new(SmithWaterman::new(2, -1, -1, -1)).backend(Backend::LinearSpace);
+        let result = aligner.align_bytes("GATTACA", "GCATGCU");
+        assert!(matches!(result, Err(Error::UnsupportedBackend)));
+        assert!(matches!(
+            aligner.backend_for(7, 7),
+            Err(Error::UnsupportedBackend)
+        ));
+
+        let aligner = aligner.backend(Backend::Adaptive(Budget::new(10)));
+        let result = aligner.align_bytes("GATTACA", "GCATGCU");
+        assert!(matches!(result, Err(Error::BudgetExceeded { .. })));
+    }
+
+    #[test]
+    fn adaptive_backend_works() {
+        let (x, y) = ("GATTACAGATTACA", "GCATGCUGCATGCU");
+        // 15 * 15 = 225 cells, taking 86 bytes packed and 353 bytes memory-mapped:
+        let linear = Aligner::new(NeedlemanWunsch::new(1, -1, -1, -1));
+        let expected = linear.align_bytes(x, y).unwrap();
+        let budgets = [
+            (Budget::new(225), Backend::InMemory),
+            (Budget::new(100), Backend::Packed),
+            (Budget::new(50).disk(400), Backend::MemoryMapped),
+            (Budget::new(50), Backend::LinearSpace),
+        ];
+        for &(budget, backend) in budgets.iter() {
+            let aligner = linear.clone().backend(Backend::Adaptive(budget));
+            let result = aligner.align_bytes(x, y).unwrap();
+            assert_eq!(result.backend(), backend);
+            assert_eq!(result.score(), expected.score());
+        }
+
+        let affine = Aligner::new(NeedlemanWunsch::affine(1, -1, -3, -1));
+        let aligner = affine.clone().backend(Backend::Adaptive(Budget::new(200)));
+        let result = aligner.align_bytes(x, y).unwrap();
+        assert_eq!(result.backend(), Backend::Packed);
+        assert_eq!(result.score(), affine.align_bytes(x, y).unwrap().score());
+
+        let aligner = affine.clone().backend(Backend::Adaptive(Budget::new(50)));
+        let result = aligner.align_bytes(x, y);
+        assert!(matches!(result, Err(Error::BudgetExceeded { .. })));
+        let aligner = affine.backend(Backend::LinearSpace);
+        let result = aligner.align_bytes(x, y);
+        assert!(matches!(result, Err(Error::UnsupportedBackend)));
+    }
+
+    #[cfg(feature = "graphemes")]
+    #[test]
+    fn graphemes_work() {
+        let aligner = Aligner::new(NeedlemanWunsch::new(1, -1, -1, -1));
+        let result = aligner.align_graphemes("ye\u{301}s", "yes").unwrap();
+        assert_eq!(result.x().len(), 3);
+        assert_eq!(result.score(), 1);
+        let formatter = AlignmentFormatter::new().show_score(false);
+        assert_eq!(
+            result.format_strs(&formatter),
+            "x 1 ye\u{301}s 3\n    | |\ny 1 yes 3\n"
+        );
+    }
+}
@@ -1,29 +1,194 @@
+use std::{fmt, ops::Range};
+
+use crate::error::Error;
 use crate::pair::cursor::Cursor;
+use crate::pair::run::Run;
 use crate::pair::runs::Runs;
+use crate::pair::score::Score;
 use crate::pair::step_mask::StepMask;
 use crate::pair::steps::Steps;
 
+/// The extent and composition of an alignment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Summary {
+    pub x_range: Range<usize>,
+    pub y_range: Range<usize>,
+    pub aligned: usize,
+    pub deleted: usize,
+    pub inserted: usize,
+    pub gap_openings: usize,
+}
+
+impl Summary {
+    fn new(origin: Cursor, steps: &[StepMask]) -> Summary {
+        match Self::try_new(origin, steps) {
+            Ok(summary) => summary,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Like `new`, but failing on step masks not being a single step instead of panicking.
+    fn try_new(origin: Cursor, steps: &[StepMask]) -> Result<Summary, Error> {
+        let mut end = origin;
+        let (mut aligned, mut deleted, mut inserted, mut gap_openings) = (0, 0, 0, 0);
+        let mut previous = StepMask::STOP;
+        for &step in steps {
+            let step = step.direction();
+            end.try_apply_forwards_step(step)?;
+            match step {
+                StepMask::ALIGN => aligned += 1,
+                StepMask::DELETE => deleted += 1,
+                StepMask::INSERT => inserted += 1,
+                _ => {}
+            }
+            if (step != StepMask::ALIGN) && (step != previous) {
+                gap_openings += 1;
+            }
+            previous = step;
+        }
+        Ok(Summary {
+            x_range: origin.x..end.x,
+            y_range: origin.y..end.y,
+            aligned,
+            deleted,
+            inserted,
+            gap_openings,
+        })
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, form: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            form,
+            "x {:?}, y {:?}: {} aligned, {} deleted, {} inserted, {} gap openings",
+            self.x_range,
+            self.y_range,
+            self.aligned,
+            self.deleted,
+            self.inserted,
+            self.gap_openings
+        )
+    }
+}
+
 #[derive(Debug)]
-pub struct Alignment {
+pub struct Alignment<N = isize> {
     origin: Cursor,
+    end: Cursor,
     steps: Vec<StepMask>,
-    score: isize,
+    score: N,
+    summary: Summary,
+    classified: bool,
 }
 
-impl Alignment {
-    pub fn new(origin: Cursor, steps: Vec<StepMask>, score: isize) -> Alignment {
+impl<N: Score> Alignment<N> {
+    pub fn new(origin: Cursor, steps: Vec<StepMask>, score: N) -> Alignment<N> {
+        let summary = Summary::new(origin, &steps);
+        Self::with_summary(origin, steps, score, summary)
+    }
+
+    /// Like `new`, but failing with `Error::InvalidStep` on any step mask
+    /// other than a single `ALIGN` (optionally with `MATCH`), `DELETE` or `INSERT`.
+    pub fn try_new(origin: Cursor, steps: Vec<StepMask>, score: N) -> Result<Alignment<N>, Error> {
+        let mut cursor = origin;
+        for &step_mask in &steps {
+            let direction = step_mask.direction();
+            let is_valid = [StepMask::ALIGN, StepMask::DELETE, StepMask::INSERT]
+                .contains(&direction)
+                && ((step_mask == direction) || (step_mask == StepMask::ALIGN | StepMask::MATCH));
+            if !is_valid {
+                return Err(Error::InvalidStep { cursor, step_mask });
+            }
+            cursor.try_apply_forwards_step(direction)?;
+        }
+        let summary = Summary::try_new(origin, &steps)?;
+        Ok(Self::with_summary(origin, steps, score, summary))
+    }
+
+    fn with_summary(
+        origin: Cursor,
+        steps: Vec<StepMask>,
+        score: N,
+        summary: Summary,
+    ) -> Alignment<N> {
+        let end = Cursor {
+            x: summary.x_range.end,
+            y: summary.y_range.end,
+        };
         Alignment {
             origin,
+            end,
             steps,
             score,
+            summary,
+            classified: false,
         }
     }
 
+    /// Creates an alignment from runs of steps, starting at `origin`.
+    pub fn from_runs<I>(runs: I, origin: Cursor, score: N) -> Alignment<N>
+    where
+        I: IntoIterator<Item = Run>,
+    {
+        let mut steps = vec![];
+        let mut classified = false;
+        for run in runs {
+            classified |= matches!(run, Run::Match { .. } | Run::Mismatch { .. });
+            steps.resize(steps.len() + run.len(), run.mask());
+        }
+        let mut alignment = Alignment::new(origin, steps, score);
+        alignment.classified = classified;
+        alignment
+    }
+
+    /// Classifies the aligned steps as matches or mismatches using `is_equal(x, y)`,
+    /// making `steps()` and `runs()` yield `Match` and `Mismatch`, rather than `Align`.
+    pub fn classify<F>(mut self, is_equal: F) -> Alignment<N>
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        let mut cursor = self.origin;
+        for step in self.steps.iter_mut() {
+            let direction = step.direction();
+            if direction == StepMask::ALIGN {
+                step.set(StepMask::MATCH, is_equal(cursor.x, cursor.y));
+            }
+            cursor.apply_forwards_step(direction);
+        }
+        self.classified = true;
+        self
+    }
+
+    /// Whether the alignment has been classified into matches and mismatches.
+    pub fn is_classified(&self) -> bool {
+        self.classified
+    }
+
     pub fn origin(&self) -> &Cursor {
         &self.origin
     }
 
-    pub fn score(&self) -> isize {
+    /// The cursor after the alignment's last step.
+    pub fn end(&self) -> &Cursor {
+        &self.end
+    }
+
+    /// The range of `x` covered by the alignment.
+    pub fn x_range(&self) -> Range<usize> {
+        self.summary.x_range.clone()
+    }
+
+    /// The range of `y` covered by the alignment.
+    pub fn y_range(&self) -> Range<usize> {
+        self.summary.y_range.clone()
+    }
+
+    pub fn summary(&self) -> &Summary {
+        &self.summary
+    }
+
+    pub fn score(&self) -> N {
         self.score
     }
 
@@ -35,8 +200,10 @@ impl Alignment {
         self.steps.is_empty()
     }
 
-    pub fn steps(&self) -> Steps {
-        Steps::new(self.steps.iter(), self.origin)
+    pub fn steps(&self) -> Steps<'_> {
+        let mut steps = Steps::new(self.steps.iter(), self.origin);
+        steps.classified = self.classified;
+        steps
     }
 
     pub fn runs(&self) -> Runs<'_> {
@@ -44,6 +211,53 @@ impl Alignment {
     }
 }
 
+// Alignments are serialized as run-length encoded steps:
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Alignment")]
+struct CompactAlignment<N> {
+    origin: Cursor,
+    runs: Vec<(StepMask, usize)>,
+    score: N,
+    #[serde(default)]
+    classified: bool,
+}
+
+#[cfg(feature = "serde")]
+impl<N: Score + serde::Serialize> serde::Serialize for Alignment<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut runs: Vec<(StepMask, usize)> = vec![];
+        for &step in &self.steps {
+            match runs.last_mut() {
+                Some((mask, len)) if *mask == step => *len += 1,
+                _ => runs.push((step, 1)),
+            }
+        }
+        let compact = CompactAlignment {
+            origin: self.origin,
+            runs,
+            score: self.score,
+            classified: self.classified,
+        };
+        serde::Serialize::serialize(&compact, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, N: Score + serde::Deserialize<'de>> serde::Deserialize<'de> for Alignment<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let compact: CompactAlignment<N> = serde::Deserialize::deserialize(deserializer)?;
+        let mut steps = vec![];
+        for (mask, len) in compact.runs {
+            steps.resize(steps.len() + len, mask);
+        }
+        let mut alignment = Alignment::try_new(compact.origin, steps, compact.score)
+            .map_err(serde::de::Error::custom)?;
+        alignment.classified = compact.classified;
+        Ok(alignment)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,4 +296,132 @@ mod tests {
     fn score_works() {
         assert_eq!(alignment().score(), score());
     }
+
+    #[test]
+    fn end_works() {
+        assert_eq!(alignment().end(), &Cursor { x: 6, y: 6 });
+        assert_eq!(alignment().x_range(), 3..6);
+        assert_eq!(alignment().y_range(), 3..6);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_works() {
+        let steps = vec![
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::DELETE,
+            StepMask::ALIGN,
+        ];
+        let alignment = Alignment::new(origin(), steps, score());
+        let json = serde_json::to_string(&alignment).unwrap();
+        assert_eq!(
+            json,
+            r#"{"origin":{"x":3,"y":3},"runs":[[2,2],[4,1],[2,1]],"score":42,"classified":false}"#
+        );
+
+        let restored: Alignment = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.origin(), alignment.origin());
+        assert_eq!(restored.score(), alignment.score());
+        assert_eq!(restored.steps, alignment.steps);
+
+        let classified = alignment.classify(|x, _| x != 5);
+        let json = serde_json::to_string(&classified).unwrap();
+        let restored: Alignment = serde_json::from_str(&json).unwrap();
+        assert!(restored.is_classified());
+        assert_eq!(restored.steps, classified.steps);
+
+        // `classified` defaults to `false` when missing:
+        let legacy = r#"{"origin":{"x":3,"y":3},"runs":[[2,2]],"score":42}"#;
+        let restored: Alignment = serde_json::from_str(legacy).unwrap();
+        assert!(!restored.is_classified());
+        assert_eq!(restored.len(), 2);
+
+        let invalid = r#"{"origin":{"x":0,"y":0},"runs":[[6,1]],"score":0}"#;
+        assert!(serde_json::from_str::<Alignment>(invalid).is_err());
+    }
+
+    #[test]
+    fn from_runs_works() {
+        let steps = vec![StepMask::INSERT, StepMask::ALIGN, StepMask::ALIGN];
+        let alignment = Alignment::new(origin(), steps.clone(), score());
+        let restored = Alignment::from_runs(alignment.runs(), origin(), score());
+        assert_eq!(restored.steps, steps);
+    }
+
+    #[test]
+    fn try_new_works() {
+        let steps = vec![StepMask::ALIGN | StepMask::MATCH, StepMask::INSERT];
+        let alignment = Alignment::try_new(origin(), steps, score()).unwrap();
+        assert_eq!(alignment.end(), &Cursor { x: 4, y: 5 });
+
+        let steps = vec![StepMask::ALIGN, StepMask::ALIGN | StepMask::DELETE];
+        let result = Alignment::try_new(origin(), steps, score());
+        let cursor = Cursor { x: 4, y: 4 };
+        assert!(matches!(result, Err(Error::InvalidStep { cursor: c, .. }) if c == cursor));
+        for &step_mask in &[StepMask::STOP, StepMask::DELETE | StepMask::DELETE_OPEN] {
+            let result = Alignment::try_new(origin(), vec![step_mask], score());
+            assert!(matches!(result, Err(Error::InvalidStep { .. })));
+        }
+    }
+
+    #[test]
+    fn classify_works() {
+        let x = b"GATTACA";
+        let y = b"GCTTAA";
+        let steps = vec![
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::DELETE,
+            StepMask::ALIGN,
+        ];
+        let alignment = Alignment::new(Cursor { x: 0, y: 0 }, steps, score());
+        assert!(!alignment.is_classified());
+        let alignment = alignment.classify(|i, j| x[i] == y[j]);
+        assert!(alignment.is_classified());
+
+        let runs: Vec<Run> = alignment.runs().collect();
+        assert_eq!(
+            runs,
+            vec![
+                Run::Match { x: 0..1, y: 0..1 },
+                Run::Mismatch { x: 1..2, y: 1..2 },
+                Run::Match { x: 2..5, y: 2..5 },
+                Run::Delete { x: 5..6 },
+                Run::Match { x: 6..7, y: 5..6 },
+            ]
+        );
+        assert_eq!(alignment.end(), &Cursor { x: 7, y: 6 });
+        assert_eq!(alignment.summary().aligned, 6);
+
+        let restored = Alignment::from_runs(runs, Cursor { x: 0, y: 0 }, score());
+        assert!(restored.is_classified());
+        assert_eq!(restored.steps, alignment.steps);
+    }
+
+    #[test]
+    fn summary_works() {
+        let steps = vec![
+            StepMask::ALIGN,
+            StepMask::DELETE,
+            StepMask::DELETE,
+            StepMask::INSERT,
+            StepMask::ALIGN,
+        ];
+        let alignment = Alignment::new(origin(), steps, score());
+        let summary = alignment.summary();
+        assert_eq!(alignment.end(), &Cursor { x: 7, y: 6 });
+        assert_eq!(
+            (summary.aligned, summary.deleted, summary.inserted),
+            (2, 2, 1)
+        );
+        assert_eq!(summary.gap_openings, 2);
+        assert_eq!(
+            summary.to_string(),
+            "x 3..7, y 3..6: 2 aligned, 2 deleted, 1 inserted, 2 gap openings"
+        );
+    }
 }
@@ -0,0 +1,153 @@
+use std::{cmp, fmt, ops::Range};
+
+use crate::error::Error;
+use crate::pair::band::Band;
+use crate::pair::cursor::Cursor;
+use crate::pair::step_mask::StepMask;
+
+use super::{cells, AlignmentMatrix as AlignmentMatrixTrait};
+
+/// An alignment matrix only storing the cells within a `Band`,
+/// using `O(radius * height)` memory.
+///
+/// Cells outside of the band read as `StepMask::STOP` and writes to them are ignored,
+/// except for those of the top row and the left column, which are always stored,
+/// so that global alignments can be traced back to the origin.
+pub struct AlignmentMatrix {
+    width: usize,
+    height: usize,
+    band: Band,
+    row_len: usize,
+    buffer: Vec<StepMask>,
+    top_row: Vec<StepMask>,
+    left_column: Vec<StepMask>,
+}
+
+impl AlignmentMatrix {
+    pub fn with_band(width: usize, height: usize, band: Band) -> Self {
+        let row_len = cmp::min(band.radius.saturating_mul(2).saturating_add(1), width);
+        let buffer = vec![StepMask::STOP; row_len * height];
+        Self {
+            width,
+            height,
+            band,
+            row_len,
+            buffer,
+            top_row: vec![StepMask::STOP; width],
+            left_column: vec![StepMask::STOP; height],
+        }
+    }
+
+    pub fn band(&self) -> &Band {
+        &self.band
+    }
+
+    fn offset(&self, cursor: &Cursor) -> Option<usize> {
+        let columns = self.columns(cursor.y);
+        if !columns.contains(&cursor.x) {
+            return None;
+        }
+        Some((cursor.x - columns.start) + (cursor.y * self.row_len))
+    }
+}
+
+impl AlignmentMatrixTrait for AlignmentMatrix {
+    type Error = Error;
+
+    /// Creates a matrix with a band wide enough to cover all cells.
+    fn new(width: usize, height: usize) -> Result<Self, Self::Error> {
+        cells(width, height)?;
+        let band = Band::main_diagonal(cmp::max(width, height));
+        Ok(Self::with_band(width, height, band))
+    }
+
+    /// Resizes the matrix, keeping its band.
+    fn resize(&mut self, width: usize, height: usize) -> Result<(), Self::Error> {
+        let row_len = cmp::min(self.band.radius.saturating_mul(2).saturating_add(1), width);
+        let cells = cells(row_len, height)?;
+        self.buffer.clear();
+        self.buffer
+            .try_reserve_exact(cells)
+            .map_err(|_| Error::Allocation { cells })?;
+        self.buffer.resize(cells, StepMask::STOP);
+        self.top_row.clear();
+        self.top_row.resize(width, StepMask::STOP);
+        self.left_column.clear();
+        self.left_column.resize(height, StepMask::STOP);
+        self.row_len = row_len;
+        self.width = width;
+        self.height = height;
+        Ok(())
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn columns(&self, y: usize) -> Range<usize> {
+        self.band.columns(y, self.width)
+    }
+
+    fn at(&self, cursor: &Cursor) -> StepMask {
+        if cursor.y == 0 {
+            return self.top_row[cursor.x];
+        }
+        if cursor.x == 0 {
+            return self.left_column[cursor.y];
+        }
+        match self.offset(cursor) {
+            Some(offset) => self.buffer[offset],
+            None => StepMask::STOP,
+        }
+    }
+
+    fn set_at(&mut self, cursor: &Cursor, step_mask: StepMask) {
+        if cursor.y == 0 {
+            self.top_row[cursor.x] = step_mask;
+        } else if cursor.x == 0 {
+            self.left_column[cursor.y] = step_mask;
+        } else if let Some(offset) = self.offset(cursor) {
+            self.buffer[offset] = step_mask;
+        }
+    }
+}
+
+impl fmt::Debug for AlignmentMatrix {
+    fn fmt(&self, form: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let cursor = Cursor { x, y };
+                let _ = write!(form, "{:?}\t", self.at(&cursor));
+            }
+            let _ = writeln!(form);
+        }
+        writeln!(form)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_band_cells_work() {
+        let mut matrix = AlignmentMatrix::with_band(100, 100, Band::main_diagonal(3));
+        assert_eq!(matrix.buffer.len(), 7 * 100);
+
+        let inside = Cursor { x: 50, y: 47 };
+        matrix.set_at(&inside, StepMask::ALIGN);
+        assert_eq!(matrix.at(&inside), StepMask::ALIGN);
+
+        let outside = Cursor { x: 50, y: 46 };
+        matrix.set_at(&outside, StepMask::ALIGN);
+        assert_eq!(matrix.at(&outside), StepMask::STOP);
+
+        let edge = Cursor { x: 50, y: 0 };
+        matrix.set_at(&edge, StepMask::DELETE);
+        assert_eq!(matrix.at(&edge), StepMask::DELETE);
+    }
+}
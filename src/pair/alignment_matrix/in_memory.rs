@@ -1,14 +1,16 @@
-use std::{fmt, mem::MaybeUninit};
+use std::fmt;
+
+use crate::error::Error;
 
 use crate::pair::cursor::Cursor;
 use crate::pair::step_mask::StepMask;
 
-use super::AlignmentMatrix as AlignmentMatrixTrait;
+use super::{cells, AlignmentMatrix as AlignmentMatrixTrait};
 
 pub struct AlignmentMatrix {
     width: usize,
     height: usize,
-    buffer: Vec<MaybeUninit<StepMask>>,
+    buffer: Vec<StepMask>,
 }
 
 impl AlignmentMatrix {
@@ -18,16 +20,28 @@ impl AlignmentMatrix {
 }
 
 impl AlignmentMatrixTrait for AlignmentMatrix {
-    // FIXME: use never type, once stabilized!
-    type Error = ();
+    type Error = Error;
 
     fn new(width: usize, height: usize) -> Result<Self, Self::Error> {
-        let buffer = vec![MaybeUninit::uninit(); width * height];
-        Ok(Self {
-            width,
-            height,
-            buffer,
-        })
+        let mut matrix = Self {
+            width: 0,
+            height: 0,
+            buffer: vec![],
+        };
+        matrix.resize(width, height)?;
+        Ok(matrix)
+    }
+
+    fn resize(&mut self, width: usize, height: usize) -> Result<(), Self::Error> {
+        let cells = cells(width, height)?;
+        let additional = cells.saturating_sub(self.buffer.len());
+        self.buffer
+            .try_reserve_exact(additional)
+            .map_err(|_| Error::Allocation { cells })?;
+        self.buffer.resize(cells, StepMask::STOP);
+        self.width = width;
+        self.height = height;
+        Ok(())
     }
 
     fn width(&self) -> usize {
@@ -39,15 +53,12 @@ impl AlignmentMatrixTrait for AlignmentMatrix {
     }
 
     fn at(&self, cursor: &Cursor) -> StepMask {
-        let offset = self.offset(cursor);
-        // Safety: Assumes `set_at` was called for this cursor, initializing the value.
-        unsafe { self.buffer[offset].assume_init_read() }
+        self.buffer[self.offset(cursor)]
     }
 
     fn set_at(&mut self, cursor: &Cursor, step_mask: StepMask) {
         let offset = self.offset(cursor);
-        // This is safe because we are writing to a `MaybeUninit` slot.
-        self.buffer[offset] = MaybeUninit::new(step_mask);
+        self.buffer[offset] = step_mask;
     }
 }
 
@@ -1,51 +1,148 @@
-use std::{fmt, fs, io, mem};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
 
 use memmap2::MmapMut;
-use tempfile::tempdir;
+use tempfile::{tempdir, TempDir};
 use uuid::Uuid;
 
+use crate::error::Error;
 use crate::pair::cursor::Cursor;
 use crate::pair::step_mask::StepMask;
 
-use super::AlignmentMatrix as AlignmentMatrixTrait;
+use super::{cells, AlignmentMatrix as AlignmentMatrixTrait};
 
+// The file starts with a header of `HEADER_LEN` bytes, followed by one byte per cell:
+//
+// - `0..4`: the magic bytes `SEAL`
+// - `4`: the format version
+// - `8..16`: the width (little-endian)
+// - `16..24`: the height (little-endian)
+// - `24..HEADER_LEN`: metadata, as written by `AlignmentSet::create()`
+const MAGIC: &[u8; 4] = b"SEAL";
+const VERSION: u8 = 1;
+const METADATA_OFFSET: usize = 24;
+pub(crate) const HEADER_LEN: usize = 128;
+
+/// An alignment matrix backed by a memory-mapped file.
+///
+/// Matrices created using `new()` live in a temporary directory that's removed once dropped,
+/// while those created using `create()` are kept and may be re-opened using `open()`.
 pub struct AlignmentMatrix {
     width: usize,
     height: usize,
     mmap: MmapMut,
+    path: PathBuf,
+    _tempdir: Option<TempDir>,
 }
 
 impl AlignmentMatrix {
-    fn offset(&self, cursor: &Cursor) -> usize {
-        cursor.x + (cursor.y * self.width)
+    /// Creates a matrix backed by a file at `path`, truncating any existing file.
+    pub fn create<P: AsRef<Path>>(path: P, width: usize, height: usize) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let size = cells(width, height)?
+            .checked_add(HEADER_LEN)
+            .ok_or(Error::SizeOverflow { width, height })?;
+        let mut mmap = map_file(&path, size)?;
+        mmap[0..4].copy_from_slice(MAGIC);
+        mmap[4] = VERSION;
+        mmap[8..16].copy_from_slice(&(width as u64).to_le_bytes());
+        mmap[16..24].copy_from_slice(&(height as u64).to_le_bytes());
+        Ok(Self {
+            width,
+            height,
+            mmap,
+            path,
+            _tempdir: None,
+        })
     }
-}
-
-impl AlignmentMatrixTrait for AlignmentMatrix {
-    type Error = io::Error;
 
-    fn new(width: usize, height: usize) -> Result<Self, Self::Error> {
-        let tempdir = tempdir()?;
-        let directory = tempdir.path();
-        let uuid = Uuid::new_v4();
-        let filename = uuid.as_simple().to_string();
-        let path = directory.join(filename);
-        let file = fs::OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&path)?;
-        let size = (width + 1) * (height + 1);
-        file.set_len(size as u64)?;
+    /// Opens a matrix previously created at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let file = fs::OpenOptions::new().read(true).write(true).open(&path)?;
         let mmap = unsafe { MmapMut::map_mut(&file)? };
+        if mmap.len() < HEADER_LEN || &mmap[0..4] != MAGIC {
+            return Err(Error::InvalidHeader("not a matrix file"));
+        }
+        if mmap[4] != VERSION {
+            return Err(Error::InvalidHeader("unsupported version"));
+        }
+        let read_usize = |range: std::ops::Range<usize>| {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(&mmap[range]);
+            usize::try_from(u64::from_le_bytes(bytes))
+                .map_err(|_| Error::InvalidHeader("size exceeds usize"))
+        };
+        let width = read_usize(8..16)?;
+        let height = read_usize(16..24)?;
+        if cells(width, height)?.checked_add(HEADER_LEN) != Some(mmap.len()) {
+            return Err(Error::InvalidHeader("size mismatch"));
+        }
         Ok(Self {
             width,
             height,
             mmap,
+            path,
+            _tempdir: None,
         })
     }
 
+    /// The path of the file backing the matrix.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Flushes outstanding changes to the file.
+    pub fn flush(&self) -> Result<(), Error> {
+        Ok(self.mmap.flush()?)
+    }
+
+    pub(crate) fn metadata(&self) -> &[u8] {
+        &self.mmap[METADATA_OFFSET..HEADER_LEN]
+    }
+
+    pub(crate) fn metadata_mut(&mut self) -> &mut [u8] {
+        &mut self.mmap[METADATA_OFFSET..HEADER_LEN]
+    }
+
+    fn offset(&self, cursor: &Cursor) -> usize {
+        HEADER_LEN + cursor.x + (cursor.y * self.width)
+    }
+}
+
+// A unique path within a new temporary directory, which is removed once dropped.
+pub(crate) fn temporary_path() -> Result<(TempDir, PathBuf), Error> {
+    let tempdir = tempdir()?;
+    let uuid = Uuid::new_v4();
+    let filename = uuid.as_simple().to_string();
+    let path = tempdir.path().join(filename);
+    Ok((tempdir, path))
+}
+
+// Creates (or truncates) the file at `path` to `size` zeroed bytes and maps it.
+pub(crate) fn map_file(path: &Path, size: usize) -> Result<MmapMut, Error> {
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.set_len(size as u64)?;
+    Ok(unsafe { MmapMut::map_mut(&file)? })
+}
+
+impl AlignmentMatrixTrait for AlignmentMatrix {
+    type Error = Error;
+
+    fn new(width: usize, height: usize) -> Result<Self, Self::Error> {
+        let (tempdir, path) = temporary_path()?;
+        let mut matrix = Self::create(path, width, height)?;
+        matrix._tempdir = Some(tempdir);
+        Ok(matrix)
+    }
+
     fn width(&self) -> usize {
         self.width
     }
@@ -55,18 +152,13 @@ impl AlignmentMatrixTrait for AlignmentMatrix {
     }
 
     fn at(&self, cursor: &Cursor) -> StepMask {
-        let offset = self.offset(cursor);
-        unsafe {
-            let byte = self.mmap.get_unchecked(offset);
-            mem::transmute::<u8, StepMask>(*byte)
-        }
+        // Re-opened files may hold arbitrary bytes, hence unknown bits are dropped:
+        StepMask::from_bits_truncate(self.mmap[self.offset(cursor)])
     }
 
     fn set_at(&mut self, cursor: &Cursor, step_mask: StepMask) {
         let offset = self.offset(cursor);
-        let byte = unsafe { mem::transmute::<StepMask, u8>(step_mask) };
-        let byte_ref = unsafe { self.mmap.get_unchecked_mut(offset) };
-        *byte_ref = byte;
+        self.mmap[offset] = step_mask.bits();
     }
 }
 
@@ -1,16 +1,49 @@
+use std::ops::Range;
+
+use crate::error::Error;
 use crate::pair::{cursor::Cursor, step_mask::StepMask};
 
+pub mod banded;
 pub mod in_memory;
 pub mod memory_mapped;
+pub mod packed;
 
 pub trait AlignmentMatrix: Sized {
     type Error;
 
+    /// Whether cells can store the affine gap flags of `StepMask`,
+    /// as required by strategies with affine gap scores.
+    ///
+    /// Defaults to `true`, so existing implementations are unaffected.
+    /// Only backends that can't hold every step mask should override it.
+    const STORES_AFFINE_FLAGS: bool = true;
+
     fn new(width: usize, height: usize) -> Result<Self, Self::Error>;
 
+    /// Resizes the matrix to `width * height` cells, reusing its allocation where possible.
+    ///
+    /// The contents of the resized matrix are unspecified.
+    fn resize(&mut self, width: usize, height: usize) -> Result<(), Self::Error> {
+        *self = Self::new(width, height)?;
+        Ok(())
+    }
+
     fn width(&self) -> usize;
     fn height(&self) -> usize;
 
+    /// The columns of row `y` that get computed, with all other cells being unreachable,
+    /// except for those of the first row and column.
+    fn columns(&self, _y: usize) -> Range<usize> {
+        0..self.width()
+    }
+
     fn at(&self, cursor: &Cursor) -> StepMask;
     fn set_at(&mut self, cursor: &Cursor, step_mask: StepMask);
 }
+
+// The number of cells of a `width * height` matrix, if it doesn't overflow.
+pub(crate) fn cells(width: usize, height: usize) -> Result<usize, Error> {
+    width
+        .checked_mul(height)
+        .ok_or(Error::SizeOverflow { width, height })
+}
@@ -0,0 +1,271 @@
+use std::fmt;
+
+use memmap2::MmapMut;
+use tempfile::TempDir;
+
+use crate::error::Error;
+use crate::pair::cursor::Cursor;
+use crate::pair::step_mask::StepMask;
+
+use super::memory_mapped::{map_file, temporary_path};
+use super::{cells, AlignmentMatrix as AlignmentMatrixTrait};
+
+/// The bytes backing a packed alignment matrix.
+pub trait Buffer: Sized {
+    /// Creates a zeroed buffer of `len` bytes.
+    fn zeroed(len: usize) -> Result<Self, Error>;
+
+    /// Resizes the buffer to `len` bytes, with unspecified contents.
+    fn resize(&mut self, len: usize) -> Result<(), Error> {
+        *self = Self::zeroed(len)?;
+        Ok(())
+    }
+
+    fn bytes(&self) -> &[u8];
+    fn bytes_mut(&mut self) -> &mut [u8];
+}
+
+impl Buffer for Vec<u8> {
+    fn zeroed(len: usize) -> Result<Self, Error> {
+        let mut buffer = vec![];
+        Buffer::resize(&mut buffer, len)?;
+        Ok(buffer)
+    }
+
+    fn resize(&mut self, len: usize) -> Result<(), Error> {
+        let additional = len.saturating_sub(self.len());
+        self.try_reserve_exact(additional)
+            .map_err(|_| Error::Allocation { cells: len })?;
+        Vec::resize(self, len, 0);
+        Ok(())
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+/// A memory-mapped file in a temporary directory, which is removed once dropped.
+pub struct MmapBuffer {
+    mmap: MmapMut,
+    _tempdir: TempDir,
+}
+
+impl Buffer for MmapBuffer {
+    fn zeroed(len: usize) -> Result<Self, Error> {
+        let (tempdir, path) = temporary_path()?;
+        let mmap = map_file(&path, len)?;
+        Ok(MmapBuffer {
+            mmap,
+            _tempdir: tempdir,
+        })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.mmap
+    }
+}
+
+/// An alignment matrix storing each cell in `BITS` bits, rather than a whole byte.
+///
+/// Cells store a `StepMask`'s direction flags in their lowest three bits,
+/// followed by its affine gap flags, if `BITS` leaves room for them.
+/// Hence `BITS = 3` suffices for linear gap penalties (storing almost three cells per byte),
+/// while affine gap penalties require `BITS = 7`.
+///
+/// `AlignmentSet::try_new()` fails with `Error::UnsupportedStrategy` for affine strategies
+/// if `BITS < 7`, while writing a step mask that doesn't fit into `BITS` bits panics.
+pub struct AlignmentMatrix<B, const BITS: usize> {
+    width: usize,
+    height: usize,
+    buffer: B,
+}
+
+impl<B: Buffer, const BITS: usize> AlignmentMatrix<B, BITS> {
+    const VALID_BITS: () = assert!((BITS >= 3) && (BITS <= 8), "BITS must be in 3..=8.");
+    const MASK: u16 = (1 << BITS) - 1;
+
+    // The number of bytes storing `width * height` cells,
+    // padded by a byte so that any cell can be read as a `u16`.
+    pub(crate) fn bytes(width: usize, height: usize) -> Result<usize, Error> {
+        let () = Self::VALID_BITS;
+        let bits = cells(width, height)?
+            .checked_mul(BITS)
+            .ok_or(Error::SizeOverflow { width, height })?;
+        Ok((bits / 8) + 2)
+    }
+
+    // The byte index and bit shift of the cell at `cursor`.
+    fn position(&self, cursor: &Cursor) -> (usize, u32) {
+        let bit = (cursor.x + (cursor.y * self.width)) * BITS;
+        (bit / 8, (bit % 8) as u32)
+    }
+}
+
+impl<B: Buffer, const BITS: usize> AlignmentMatrixTrait for AlignmentMatrix<B, BITS> {
+    type Error = Error;
+
+    const STORES_AFFINE_FLAGS: bool = BITS >= 7;
+
+    fn new(width: usize, height: usize) -> Result<Self, Self::Error> {
+        let buffer = B::zeroed(Self::bytes(width, height)?)?;
+        Ok(Self {
+            width,
+            height,
+            buffer,
+        })
+    }
+
+    fn resize(&mut self, width: usize, height: usize) -> Result<(), Self::Error> {
+        self.buffer.resize(Self::bytes(width, height)?)?;
+        self.width = width;
+        self.height = height;
+        Ok(())
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn at(&self, cursor: &Cursor) -> StepMask {
+        let (index, shift) = self.position(cursor);
+        let bytes = self.buffer.bytes();
+        let word = u16::from_le_bytes([bytes[index], bytes[index + 1]]);
+        let bits = ((word >> shift) & Self::MASK) as u8;
+        // Cells don't store `StepMask::MATCH`, which is only used by alignments:
+        StepMask::from_bits_truncate(bits << 1)
+    }
+
+    fn set_at(&mut self, cursor: &Cursor, step_mask: StepMask) {
+        let bits = u16::from(step_mask.bits() >> 1);
+        assert!(
+            bits <= Self::MASK,
+            "Step mask {:?} doesn't fit into {} bits.",
+            step_mask,
+            BITS
+        );
+        let (index, shift) = self.position(cursor);
+        let bytes = self.buffer.bytes_mut();
+        let mut word = u16::from_le_bytes([bytes[index], bytes[index + 1]]);
+        word &= !(Self::MASK << shift);
+        word |= bits << shift;
+        bytes[index..(index + 2)].copy_from_slice(&word.to_le_bytes());
+    }
+}
+
+impl<B: Buffer, const BITS: usize> fmt::Debug for AlignmentMatrix<B, BITS> {
+    fn fmt(&self, form: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let cursor = Cursor { x, y };
+                let _ = write!(form, "{:?}\t", self.at(&cursor));
+            }
+            let _ = writeln!(form);
+        }
+        writeln!(form)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{
+        AlignmentSet, InMemoryAlignmentMatrix, NeedlemanWunsch, PackedAlignmentMatrix,
+        PackedMemoryMappedAlignmentMatrix, Strategy,
+    };
+
+    #[test]
+    fn cells_work() {
+        let mut matrix: PackedAlignmentMatrix = AlignmentMatrixTrait::new(7, 5).unwrap();
+        assert_eq!(matrix.buffer.len(), (7 * 5 * 3 / 8) + 2);
+        let masks = [
+            StepMask::ALIGN,
+            StepMask::DELETE,
+            StepMask::INSERT,
+            StepMask::ALIGN | StepMask::INSERT,
+            StepMask::STOP,
+        ];
+        for y in 0..5 {
+            for x in 0..7 {
+                matrix.set_at(&Cursor { x, y }, masks[(x + y) % masks.len()]);
+            }
+        }
+        for y in 0..5 {
+            for x in 0..7 {
+                assert_eq!(matrix.at(&Cursor { x, y }), masks[(x + y) % masks.len()]);
+            }
+        }
+    }
+
+    #[test]
+    fn affine_flags_dont_fit() {
+        let affine = NeedlemanWunsch::affine(1, -1, -3, -1);
+        let f = |i: usize, j: usize| i == j;
+        let result: Result<AlignmentSet<PackedAlignmentMatrix>, _> =
+            AlignmentSet::try_new(2, 2, affine.clone(), f);
+        assert!(matches!(result, Err(Error::UnsupportedStrategy(_))));
+        let result: Result<AlignmentSet<PackedAlignmentMatrix<7>>, _> =
+            AlignmentSet::try_new(2, 2, affine, f);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn affine_flags_dont_fit_into_cells() {
+        let mut matrix: PackedAlignmentMatrix = AlignmentMatrixTrait::new(2, 2).unwrap();
+        matrix.set_at(
+            &Cursor { x: 1, y: 1 },
+            StepMask::INSERT | StepMask::INSERT_OPEN,
+        );
+    }
+
+    fn assert_matches<M, S>(strategy: S)
+    where
+        M: AlignmentMatrixTrait,
+        M::Error: fmt::Debug,
+        S: Strategy<Score = isize> + Clone,
+    {
+        let (x, y) = (b"ACCGTTTGATTACAGGGGTACCA", b"CCGTAGTTACTTCAGGGGCCA");
+        let f = |i: usize, j: usize| x[i] == y[j];
+        let packed: AlignmentSet<M> =
+            AlignmentSet::new(x.len(), y.len(), strategy.clone(), f).unwrap();
+        let expected: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy, f).unwrap();
+        let cigars = |alignments: &mut dyn Iterator<Item = crate::pair::Alignment>| {
+            alignments
+                .map(|a| a.cigar(y.len()).to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            cigars(&mut packed.global_alignments()),
+            cigars(&mut expected.global_alignments())
+        );
+        assert_eq!(
+            cigars(&mut packed.local_alignments()),
+            cigars(&mut expected.local_alignments())
+        );
+    }
+
+    #[test]
+    fn alignments_work() {
+        let linear = NeedlemanWunsch::new(1, -1, -1, -1);
+        let affine = NeedlemanWunsch::affine(1, -1, -3, -1);
+        assert_matches::<PackedAlignmentMatrix, _>(linear.clone());
+        assert_matches::<PackedAlignmentMatrix<4>, _>(linear.clone());
+        assert_matches::<PackedMemoryMappedAlignmentMatrix, _>(linear);
+        assert_matches::<PackedAlignmentMatrix<7>, _>(affine.clone());
+        assert_matches::<PackedMemoryMappedAlignmentMatrix<7>, _>(affine);
+    }
+}
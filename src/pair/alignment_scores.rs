@@ -0,0 +1,125 @@
+use crate::pair::alignment_matrix::AlignmentMatrix;
+use crate::pair::alignment_set::{AlignmentSet, Scratch};
+use crate::pair::cursor::Cursor;
+use crate::pair::score::Score;
+use crate::pair::step_mask::StepMask;
+use crate::pair::strategy::Strategy;
+use crate::pair::substitution::Substitution;
+
+// A matrix of the right dimensions that discards all step masks.
+struct Discarding {
+    width: usize,
+    height: usize,
+}
+
+impl AlignmentMatrix for Discarding {
+    type Error = ();
+
+    fn new(width: usize, height: usize) -> Result<Self, Self::Error> {
+        Ok(Discarding { width, height })
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn at(&self, _cursor: &Cursor) -> StepMask {
+        StepMask::STOP
+    }
+
+    fn set_at(&mut self, _cursor: &Cursor, _step_mask: StepMask) {}
+}
+
+/// The optimal scores of aligning two sequences, computed without a traceback matrix.
+///
+/// Only a single row of scores is kept in memory, making this
+/// suitable for screening large numbers of sequence pairs.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AlignmentScores<N = isize> {
+    local_score: N,
+    global_score: N,
+    local_max: Cursor,
+    global_max: Cursor,
+}
+
+impl<N: Score> AlignmentScores<N> {
+    pub fn new<S, F, R>(x_len: usize, y_len: usize, strategy: S, f: F) -> AlignmentScores<N>
+    where
+        S: Strategy<Score = N>,
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
+    {
+        Self::with_scratch(x_len, y_len, &strategy, f, &mut Scratch::default())
+    }
+
+    pub(crate) fn with_scratch<S, F, R>(
+        x_len: usize,
+        y_len: usize,
+        strategy: &S,
+        f: F,
+        scratch: &mut Scratch<N>,
+    ) -> AlignmentScores<N>
+    where
+        S: Strategy<Score = N>,
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
+    {
+        let matrix = Discarding {
+            width: x_len + 1,
+            height: y_len + 1,
+        };
+        let set = AlignmentSet::with_scratch(matrix, strategy, f, scratch);
+        AlignmentScores {
+            local_score: set.local_score(),
+            global_score: set.global_score(),
+            local_max: *set.local_max(),
+            global_max: *set.global_max(),
+        }
+    }
+
+    pub fn local_score(&self) -> N {
+        self.local_score
+    }
+
+    pub fn global_score(&self) -> N {
+        self.global_score
+    }
+
+    pub fn local_max(&self) -> &Cursor {
+        &self.local_max
+    }
+
+    pub fn global_max(&self) -> &Cursor {
+        &self.global_max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{InMemoryAlignmentMatrix, NeedlemanWunsch, SmithWaterman};
+
+    #[test]
+    fn scores_match_alignment_set() {
+        let (x, y) = (b"xxxxGATTACAyyyy", b"zzGCATGCAzz");
+        let f = |i: usize, j: usize| x[i] == y[j];
+
+        let strategy = NeedlemanWunsch::new(1, -1, -1, -1);
+        let scores = AlignmentScores::new(x.len(), y.len(), strategy.clone(), f);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy, f).unwrap();
+        assert_eq!(scores.global_score(), set.global_score());
+        assert_eq!(scores.global_max(), set.global_max());
+
+        let strategy = SmithWaterman::affine(2, -1, -2, -1);
+        let scores = AlignmentScores::new(x.len(), y.len(), strategy.clone(), f);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy, f).unwrap();
+        assert_eq!(scores.local_score(), set.local_score());
+        assert_eq!(scores.local_max(), set.local_max());
+    }
+}
@@ -1,131 +1,429 @@
-use std::cmp;
+use std::{cmp, ops::Range};
 
+use crate::error::Error;
 use crate::pair::alignment::Alignment;
-use crate::pair::alignment_matrix::AlignmentMatrix;
+use crate::pair::alignment_matrix::{
+    banded::AlignmentMatrix as BandedAlignmentMatrix, AlignmentMatrix,
+};
 use crate::pair::alignments::Alignments;
+use crate::pair::band::Band;
 use crate::pair::cursor::Cursor;
+use crate::pair::end_gaps::EndGaps;
+use crate::pair::score::{self, Score};
 use crate::pair::step_mask::StepMask;
 use crate::pair::strategy::Strategy;
+use crate::pair::substitution::Substitution;
+
+mod persistence;
+#[cfg(feature = "parallel")]
+mod wavefront;
 
 #[derive(Copy, Clone)]
-struct Highscore {
-    score: isize,
+struct Highscore<N> {
+    score: N,
     cursor: Cursor,
 }
 
-struct Highscores {
-    local: Highscore,
-    global: Highscore,
+struct Highscores<N> {
+    local: Highscore<N>,
+    global: Highscore<N>,
 }
 
-impl Highscores {
-    fn update(&mut self, highscore: Highscore) {
+impl<N: Score> Highscores<N> {
+    fn update(&mut self, highscore: Highscore<N>) {
         if highscore.score >= self.local.score {
             self.local = highscore
         }
-        self.global = highscore;
     }
 }
 
-pub struct AlignmentSet<T> {
+// Score buffers of a fill, to be reused across alignments.
+pub(crate) struct Scratch<N> {
+    row: Vec<N>,
+    insert_row: Vec<N>,
+}
+
+impl<N> Default for Scratch<N> {
+    fn default() -> Self {
+        Scratch {
+            row: vec![],
+            insert_row: vec![],
+        }
+    }
+}
+
+pub struct AlignmentSet<T, N = isize> {
     matrix: T,
-    highscores: Highscores,
+    highscores: Highscores<N>,
 }
 
-impl<T, E> AlignmentSet<T>
+impl<T, E, N> AlignmentSet<T, N>
 where
     T: AlignmentMatrix<Error = E>,
+    N: Score,
 {
-    pub fn new<S: Strategy, F>(
+    pub fn new<S: Strategy<Score = N>, F, R>(
         x_len: usize,
         y_len: usize,
         strategy: S,
         f: F,
-    ) -> Result<AlignmentSet<T>, E>
+    ) -> Result<AlignmentSet<T, N>, E>
     where
-        F: Fn(usize, usize) -> bool,
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
     {
         let width = x_len + 1;
         let height = y_len + 1;
 
-        let mut matrix = T::new(width, height)?;
+        let matrix = T::new(width, height)?;
+
+        Ok(Self::with_matrix(matrix, strategy, f))
+    }
+
+    /// Like `new`, but rejecting empty sequences and affine strategies for matrices
+    /// not storing affine gap flags, failing with the crate's `Error`.
+    pub fn try_new<S: Strategy<Score = N>, F, R>(
+        x_len: usize,
+        y_len: usize,
+        strategy: S,
+        f: F,
+    ) -> Result<AlignmentSet<T, N>, Error>
+    where
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
+        Error: From<E>,
+    {
+        if x_len == 0 || y_len == 0 {
+            return Err(Error::EmptySequence);
+        }
+        if strategy.has_affine_gaps() && !T::STORES_AFFINE_FLAGS {
+            return Err(Error::UnsupportedStrategy(
+                "affine gaps require a matrix storing affine gap flags",
+            ));
+        }
+        let width = x_len.saturating_add(1);
+        let height = y_len.saturating_add(1);
+
+        let matrix = T::new(width, height)?;
 
-        Self::prepare_matrix(&mut matrix);
-        let mut row = Self::prepared_row(width, &strategy);
+        Ok(Self::with_matrix(matrix, strategy, f))
+    }
+
+    /// Fills a pre-allocated `matrix` of `(x_len + 1) * (y_len + 1)` cells,
+    /// only computing the cells given by `AlignmentMatrix::columns()`.
+    pub fn with_matrix<S, F, R>(matrix: T, strategy: S, f: F) -> AlignmentSet<T, N>
+    where
+        S: Strategy<Score = N>,
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
+    {
+        Self::with_scratch(matrix, &strategy, f, &mut Scratch::default())
+    }
+
+    pub(crate) fn with_scratch<S, F, R>(
+        mut matrix: T,
+        strategy: &S,
+        f: F,
+        scratch: &mut Scratch<N>,
+    ) -> AlignmentSet<T, N>
+    where
+        S: Strategy<Score = N>,
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
+    {
+        assert!(
+            !strategy.has_affine_gaps() || T::STORES_AFFINE_FLAGS,
+            "Affine gaps require a matrix storing affine gap flags."
+        );
+        let highscores = if strategy.has_affine_gaps() {
+            Self::fill_affine(&mut matrix, strategy, f, scratch)
+        } else {
+            Self::fill_linear(&mut matrix, strategy, f, scratch)
+        };
+
+        AlignmentSet { matrix, highscores }
+    }
+
+    fn fill_linear<S, F, R>(
+        matrix: &mut T,
+        strategy: &S,
+        f: F,
+        scratch: &mut Scratch<N>,
+    ) -> Highscores<N>
+    where
+        S: Strategy<Score = N>,
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
+    {
+        let y_len = matrix.height() - 1;
+
+        Self::prepare_matrix(matrix, strategy);
+        let row = &mut scratch.row;
+        Self::prepare_row(row, matrix, strategy);
         let mut highscores = Self::prepared_highscores();
+        let mut last_column = Self::prepared_last_column(row);
 
-        for y in 0..y_len {
-            let mut last_diagonal = strategy.total_score(strategy.mismatch_score() * (y as isize));
-            row[0] = strategy.total_score(strategy.insert_score() * ((y + 1) as isize));
-            for x in 0..x_len {
-                let previous = (last_diagonal, row[x], row[x + 1]);
-                let equal = f(x, y);
-                let (steps, score) = Self::calculate_cell(&strategy, previous, equal);
-                let cursor = Cursor { x: x + 1, y: y + 1 };
+        let mut previous_columns = 0..matrix.width();
+        for y in 1..=y_len {
+            let columns = matrix.columns(y);
+            let start = cmp::max(columns.start, 1);
+            let mut last_diagonal = row[start - 1];
+            Self::clear_row(row, &previous_columns, &columns);
+            row[0] = Self::leading_insert_score(strategy, y);
+            for x in start..columns.end {
+                let previous = (last_diagonal, row[x - 1], row[x]);
+                let substitution = f(x - 1, y - 1).score(strategy);
+                let (steps, score) = Self::calculate_cell(strategy, previous, substitution);
+                let cursor = Cursor { x, y };
                 highscores.update(Highscore { cursor, score });
                 matrix.set_at(&cursor, steps);
-                let old_diagonal = row[x + 1];
-                row[x + 1] = score;
-                last_diagonal = old_diagonal;
+                last_diagonal = row[x];
+                row[x] = score;
             }
+            Self::update_last_column(&mut last_column, row, y);
+            previous_columns = columns;
         }
 
-        Ok(AlignmentSet { matrix, highscores })
+        highscores.global = Self::global_highscore(strategy, row, last_column, y_len);
+        highscores
     }
 
-    fn calculate_cell<S: Strategy>(
+    // Gotoh's three-state recurrence, tracking the best score ending in an
+    // insertion (`insert_row`, per column) and in a deletion (`delete`, per row)
+    // next to the overall best score (`row`).
+    fn fill_affine<S, F, R>(
+        matrix: &mut T,
         strategy: &S,
-        previous_scores: (isize, isize, isize),
-        equal: bool,
-    ) -> (StepMask, isize) {
-        let (mut align, mut delete, mut insert) = previous_scores;
-        align += if equal {
-            strategy.match_score()
-        } else {
-            strategy.mismatch_score()
-        };
-        delete += strategy.delete_score();
-        insert += strategy.insert_score();
-        let steps = StepMask::from_scores(align, delete, insert);
-        let score = strategy.total_score(cmp::max(cmp::max(align, delete), insert));
+        f: F,
+        scratch: &mut Scratch<N>,
+    ) -> Highscores<N>
+    where
+        S: Strategy<Score = N>,
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
+    {
+        let y_len = matrix.height() - 1;
+
+        Self::prepare_matrix(matrix, strategy);
+        let row = &mut scratch.row;
+        Self::prepare_row(row, matrix, strategy);
+        let insert_row = &mut scratch.insert_row;
+        insert_row.clear();
+        insert_row.resize(matrix.width(), N::MIN);
+        let mut highscores = Self::prepared_highscores();
+        let mut last_column = Self::prepared_last_column(row);
+
+        let mut previous_columns = 0..matrix.width();
+        for y in 1..=y_len {
+            let columns = matrix.columns(y);
+            let start = cmp::max(columns.start, 1);
+            let mut last_diagonal = row[start - 1];
+            Self::clear_row(row, &previous_columns, &columns);
+            row[0] = Self::leading_insert_score(strategy, y);
+            let mut delete = N::MIN;
+            for x in start..columns.end {
+                let align = last_diagonal.saturating_add(f(x - 1, y - 1).score(strategy));
+                let up = (row[x], insert_row[x]);
+                let left = (row[x - 1], delete);
+                let (steps, score, insert, deleted) =
+                    Self::calculate_affine_cell(strategy, align, up, left);
+                let cursor = Cursor { x, y };
+                highscores.update(Highscore { cursor, score });
+                matrix.set_at(&cursor, steps);
+                insert_row[x] = insert;
+                delete = deleted;
+                last_diagonal = row[x];
+                row[x] = score;
+            }
+            Self::update_last_column(&mut last_column, row, y);
+            previous_columns = columns;
+        }
+
+        highscores.global = Self::global_highscore(strategy, row, last_column, y_len);
+        highscores
+    }
+
+    // Marks cells that dropped out of the computed columns as unreachable.
+    fn clear_row(row: &mut [N], previous_columns: &Range<usize>, columns: &Range<usize>) {
+        let (start, end) = (previous_columns.start, previous_columns.end);
+        let left = start..columns.start.clamp(start, end);
+        let right = columns.end.clamp(start, end)..end;
+        for columns in [left, right] {
+            for score in &mut row[columns] {
+                *score = N::MIN;
+            }
+        }
+    }
+
+    fn calculate_cell<S: Strategy<Score = N>>(
+        strategy: &S,
+        previous_scores: (N, N, N),
+        substitution: N,
+    ) -> (StepMask, N) {
+        let (align, delete, insert) = previous_scores;
+        let align = align.saturating_add(substitution);
+        let delete = delete.saturating_add(strategy.delete_score());
+        let insert = insert.saturating_add(strategy.insert_score());
+        let steps = strategy.step_mask(align, insert, delete);
+        let score = strategy.total_score(score::max(score::max(align, delete), insert));
         (steps, score)
     }
 
-    fn prepare_matrix(matrix: &mut T) {
+    // Returns the step mask and the best overall, insertion and deletion scores
+    // of a cell, given the scores of the (overall, insertion) state above it
+    // and of the (overall, deletion) state to its left.
+    fn calculate_affine_cell<S: Strategy<Score = N>>(
+        strategy: &S,
+        align: N,
+        up: (N, N),
+        left: (N, N),
+    ) -> (StepMask, N, N, N) {
+        let insert_extend = strategy.insert_score();
+        let delete_extend = strategy.delete_score();
+        let insert_open = strategy.insert_open_score().saturating_add(insert_extend);
+        let delete_open = strategy.delete_open_score().saturating_add(delete_extend);
+        let (insert_steps, insert) = StepMask::from_gap_scores(
+            up.1.saturating_add(insert_extend),
+            up.0.saturating_add(insert_open),
+            StepMask::INSERT_EXTEND,
+            StepMask::INSERT_OPEN,
+        );
+        let (delete_steps, delete) = StepMask::from_gap_scores(
+            left.1.saturating_add(delete_extend),
+            left.0.saturating_add(delete_open),
+            StepMask::DELETE_EXTEND,
+            StepMask::DELETE_OPEN,
+        );
+        let steps = strategy.step_mask(align, insert, delete) | insert_steps | delete_steps;
+        let score = strategy.total_score(score::max(score::max(align, delete), insert));
+        (steps, score, insert, delete)
+    }
+
+    fn prepare_matrix<S: Strategy<Score = N>>(matrix: &mut T, strategy: &S) {
+        let end_gaps = strategy.free_end_gaps();
+        let affine = strategy.has_affine_gaps();
+        matrix.set_at(&Cursor { x: 0, y: 0 }, StepMask::STOP);
         for y in 1..matrix.height() {
             let cursor = Cursor { x: 0, y };
-            matrix.set_at(&cursor, StepMask::INSERT);
+            let steps = if end_gaps.contains(EndGaps::Y_LEADING) {
+                StepMask::STOP
+            } else {
+                Self::leading_gap_steps(StepMask::INSERT, y, affine)
+            };
+            matrix.set_at(&cursor, steps);
         }
         for x in 1..matrix.width() {
             let cursor = Cursor { x, y: 0 };
-            matrix.set_at(&cursor, StepMask::DELETE);
+            let steps = if end_gaps.contains(EndGaps::X_LEADING) {
+                StepMask::STOP
+            } else {
+                Self::leading_gap_steps(StepMask::DELETE, x, affine)
+            };
+            matrix.set_at(&cursor, steps);
+        }
+    }
+
+    fn leading_gap_steps(mask: StepMask, len: usize, affine: bool) -> StepMask {
+        if !affine {
+            return mask;
+        }
+        let (extend, open) = match mask {
+            StepMask::INSERT => (StepMask::INSERT_EXTEND, StepMask::INSERT_OPEN),
+            _ => (StepMask::DELETE_EXTEND, StepMask::DELETE_OPEN),
+        };
+        if len == 1 {
+            mask | open
+        } else {
+            mask | extend
+        }
+    }
+
+    fn leading_insert_score<S: Strategy<Score = N>>(strategy: &S, len: usize) -> N {
+        if strategy.free_end_gaps().contains(EndGaps::Y_LEADING) {
+            return strategy.total_score(N::ZERO);
+        }
+        let gap = strategy.insert_score().times(len);
+        strategy.total_score(strategy.insert_open_score().saturating_add(gap))
+    }
+
+    fn leading_delete_score<S: Strategy<Score = N>>(strategy: &S, len: usize) -> N {
+        if (len == 0) || strategy.free_end_gaps().contains(EndGaps::X_LEADING) {
+            return strategy.total_score(N::ZERO);
+        }
+        let gap = strategy.delete_score().times(len);
+        strategy.total_score(strategy.delete_open_score().saturating_add(gap))
+    }
+
+    // The first row and column are always reachable, regardless of `AlignmentMatrix::columns()`.
+    fn prepare_row<S: Strategy<Score = N>>(row: &mut Vec<N>, matrix: &T, strategy: &S) {
+        row.clear();
+        row.extend((0..matrix.width()).map(|x| Self::leading_delete_score(strategy, x)));
+    }
+
+    fn prepared_last_column(row: &[N]) -> Highscore<N> {
+        let x = row.len() - 1;
+        Highscore {
+            score: row[x],
+            cursor: Cursor { x, y: 0 },
         }
     }
 
-    fn prepared_row<S: Strategy>(width: usize, strategy: &S) -> Vec<isize> {
-        (0..width)
-            .map(|i| strategy.total_score(strategy.delete_score() * (i as isize)))
-            .collect()
+    fn update_last_column(last_column: &mut Highscore<N>, row: &[N], y: usize) {
+        let x = row.len() - 1;
+        if row[x] > last_column.score {
+            *last_column = Highscore {
+                score: row[x],
+                cursor: Cursor { x, y },
+            };
+        }
     }
 
-    fn prepared_highscores() -> Highscores {
+    // The global alignment ends in the bottom-right corner, unless trailing
+    // gaps are free, in which case it may also end in the last row/column.
+    fn global_highscore<S: Strategy<Score = N>>(
+        strategy: &S,
+        last_row: &[N],
+        last_column: Highscore<N>,
+        y_len: usize,
+    ) -> Highscore<N> {
+        let end_gaps = strategy.free_end_gaps();
+        let x_len = last_row.len() - 1;
+        let mut highscore = Highscore {
+            score: last_row[x_len],
+            cursor: Cursor { x: x_len, y: y_len },
+        };
+        if end_gaps.contains(EndGaps::X_TRAILING) {
+            for (x, &score) in last_row.iter().enumerate() {
+                if score > highscore.score {
+                    let cursor = Cursor { x, y: y_len };
+                    highscore = Highscore { score, cursor };
+                }
+            }
+        }
+        if end_gaps.contains(EndGaps::Y_TRAILING) && (last_column.score > highscore.score) {
+            highscore = last_column;
+        }
+        highscore
+    }
+
+    fn prepared_highscores() -> Highscores<N> {
         Highscores {
             local: Highscore {
-                score: 0,
+                score: N::ZERO,
                 cursor: Cursor { x: 0, y: 0 },
             },
             global: Highscore {
-                score: 0,
+                score: N::ZERO,
                 cursor: Cursor { x: 0, y: 0 },
             },
         }
     }
 
-    pub fn local_score(&self) -> isize {
+    pub fn local_score(&self) -> N {
         self.highscores.local.score
     }
 
-    pub fn global_score(&self) -> isize {
+    pub fn global_score(&self) -> N {
         self.highscores.global.score
     }
 
@@ -137,20 +435,30 @@ where
         &self.highscores.global.cursor
     }
 
-    pub fn local_alignment(&self) -> Alignment {
+    pub fn local_alignment(&self) -> Alignment<N> {
         self.local_alignments().next().unwrap()
     }
 
-    pub fn global_alignment(&self) -> Alignment {
+    pub fn global_alignment(&self) -> Alignment<N> {
         self.global_alignments().next().unwrap()
     }
 
-    pub fn local_alignments(&self) -> Alignments<'_, T> {
+    /// Like `local_alignment`, but failing instead of panicking.
+    pub fn try_local_alignment(&self) -> Result<Alignment<N>, Error> {
+        self.local_alignments().next().ok_or(Error::NoAlignment)
+    }
+
+    /// Like `global_alignment`, but failing instead of panicking.
+    pub fn try_global_alignment(&self) -> Result<Alignment<N>, Error> {
+        self.global_alignments().next().ok_or(Error::NoAlignment)
+    }
+
+    pub fn local_alignments(&self) -> Alignments<'_, T, N> {
         let stack = vec![(StepMask::STOP, self.highscores.local.cursor, 0)];
         Alignments::new(&self.matrix, stack, vec![], self.highscores.local.score)
     }
 
-    pub fn global_alignments(&self) -> Alignments<'_, T> {
+    pub fn global_alignments(&self) -> Alignments<'_, T, N> {
         let stack = vec![(StepMask::STOP, self.highscores.global.cursor, 0)];
         Alignments::new(&self.matrix, stack, vec![], self.highscores.global.score)
     }
@@ -158,4 +466,263 @@ where
     pub fn matrix(&self) -> &T {
         &self.matrix
     }
+
+    /// Returns the matrix, e.g. for reusing its allocation via `AlignmentMatrix::resize()`.
+    pub fn into_matrix(self) -> T {
+        self.matrix
+    }
+}
+
+impl<N: Score> AlignmentSet<BandedAlignmentMatrix, N> {
+    /// Only computes and stores the cells within `band`, treating all other cells as unreachable.
+    ///
+    /// Use `Band::touches_edge()` to check whether an alignment might have been
+    /// constrained by the band, in which case a wider band might yield a better one.
+    pub fn banded<S: Strategy<Score = N>, F, R>(
+        x_len: usize,
+        y_len: usize,
+        band: Band,
+        strategy: S,
+        f: F,
+    ) -> AlignmentSet<BandedAlignmentMatrix, N>
+    where
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
+    {
+        let matrix = BandedAlignmentMatrix::with_band(x_len + 1, y_len + 1, band);
+        Self::with_matrix(matrix, strategy, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{
+        InMemoryAlignmentMatrix, NeedlemanWunsch, SemiGlobal, SmithWaterman, Step, BLOSUM62,
+    };
+
+    fn alignment_set<F, R>(x: &[u8], y: &[u8], f: F) -> AlignmentSet<InMemoryAlignmentMatrix>
+    where
+        F: Fn(usize, usize) -> R,
+        R: Substitution<isize>,
+    {
+        let strategy = NeedlemanWunsch::new(1, -1, -1, -1);
+        AlignmentSet::new(x.len(), y.len(), strategy, f).unwrap()
+    }
+
+    #[test]
+    fn equality_works() {
+        let (x, y) = (b"GATTACA", b"GCATGCA");
+        let set = alignment_set(x, y, |i, j| x[i] == y[j]);
+        assert_eq!(set.global_score(), 2);
+        assert_eq!(set.global_max(), &Cursor { x: 7, y: 7 });
+    }
+
+    #[test]
+    fn generic_scores_work() {
+        let (x, y) = (b"GATTACA", b"GCATGCA");
+        let strategy = NeedlemanWunsch::new(0.5, -0.5, -0.5, -0.5);
+        let set: AlignmentSet<InMemoryAlignmentMatrix, f64> =
+            AlignmentSet::new(x.len(), y.len(), strategy, |i, j| x[i] == y[j]).unwrap();
+        assert_eq!(set.global_score(), 1.0);
+        assert_eq!(set.global_alignment().score(), 1.0);
+
+        let strategy = SmithWaterman::affine(2i16, -1, -3, -1);
+        let set: AlignmentSet<InMemoryAlignmentMatrix, i16> =
+            AlignmentSet::new(x.len(), y.len(), strategy, |i, j| x[i] == y[j]).unwrap();
+        assert_eq!(set.local_score(), 5);
+    }
+
+    #[test]
+    fn substitution_scores_work() {
+        let (x, y) = (b"HEAGAWGHEE", b"PAWHEAE");
+        let set = alignment_set(x, y, |i, j| BLOSUM62.score::<isize, _>(x[i], y[j]));
+        let expected = alignment_set(x, y, |i, j| x[i] == y[j]);
+        assert_ne!(set.global_score(), expected.global_score());
+
+        let alignment = set.global_alignment();
+        let mut score = 0;
+        for step in alignment.steps() {
+            score += match step {
+                Step::Align { x: i, y: j } => BLOSUM62.score::<isize, _>(x[i], y[j]),
+                _ => -1,
+            };
+        }
+        assert_eq!(score, set.global_score());
+    }
+
+    fn affine_score(
+        alignment: &Alignment,
+        x: &[u8],
+        y: &[u8],
+        open: isize,
+        extend: isize,
+    ) -> isize {
+        let mut score = 0;
+        let mut previous = StepMask::STOP;
+        for step in alignment.steps() {
+            score += match step {
+                Step::Align { x: i, y: j } if x[i] == y[j] => 1,
+                Step::Align { .. } => -1,
+                _ if step.mask() == previous => extend,
+                _ => open + extend,
+            };
+            previous = step.mask();
+        }
+        score
+    }
+
+    #[test]
+    fn affine_gaps_work() {
+        let (x, y) = (b"AAACCCGGGTTT", b"AAATCTTT");
+        let strategy = NeedlemanWunsch::affine(1, -1, -5, -1);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy, |i, j| x[i] == y[j]).unwrap();
+
+        let alignment = set.global_alignment();
+        let gaps = alignment
+            .steps()
+            .zip(alignment.steps().skip(1))
+            .filter(|(a, b)| a.mask() != StepMask::DELETE && b.mask() == StepMask::DELETE)
+            .count();
+        assert_eq!(gaps, 1);
+
+        let mut count = 0;
+        for alignment in set.global_alignments() {
+            assert_eq!(affine_score(&alignment, x, y, -5, -1), set.global_score());
+            count += 1;
+        }
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn affine_local_gaps_work() {
+        let (x, y) = (b"TTTTACGTACGTAAAAACGTACGTTTTT", b"GGACGTACGTACGTACGTGG");
+        let strategy = SmithWaterman::affine(2, -1, -3, -1);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy, |i, j| x[i] == y[j]).unwrap();
+        assert_eq!(set.local_score(), 16 * 2 - 3 - 4);
+    }
+
+    #[test]
+    fn local_alignment_works() {
+        let (x, y) = (b"xxxxABCDEFyyyy", b"zzABCDEFzz");
+        let strategy = SmithWaterman::new(2, -1, -1, -1);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy, |i, j| x[i] == y[j]).unwrap();
+        let alignment = set.local_alignment();
+        assert_eq!(alignment.origin(), &Cursor { x: 4, y: 2 });
+        assert_eq!(alignment.len(), 6);
+        assert!(alignment.steps().all(|step| step.mask() == StepMask::ALIGN));
+        assert_eq!(set.local_score(), 12);
+    }
+
+    #[test]
+    fn fitting_alignment_works() {
+        let (x, y) = (b"TTTTTGATTACATTTTT", b"GATTACA");
+        let end_gaps = EndGaps::X_LEADING | EndGaps::X_TRAILING;
+        let strategy = SemiGlobal::new(NeedlemanWunsch::new(1, -1, -1, -1), end_gaps);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy, |i, j| x[i] == y[j]).unwrap();
+        assert_eq!(set.global_score(), 7);
+        assert_eq!(set.global_max(), &Cursor { x: 12, y: 7 });
+        let alignment = set.global_alignment();
+        assert_eq!(alignment.origin(), &Cursor { x: 5, y: 0 });
+        assert_eq!(alignment.len(), 7);
+    }
+
+    #[test]
+    fn overlap_alignment_works() {
+        let (x, y) = (b"CCCCGATTACA", b"GATTACAGGGGGG");
+        let end_gaps = EndGaps::X_LEADING | EndGaps::Y_TRAILING;
+        let strategy = SemiGlobal::new(NeedlemanWunsch::affine(1, -1, -2, -1), end_gaps);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy, |i, j| x[i] == y[j]).unwrap();
+        assert_eq!(set.global_score(), 7);
+        assert_eq!(set.global_max(), &Cursor { x: 11, y: 7 });
+        let alignment = set.global_alignment();
+        assert_eq!(alignment.origin(), &Cursor { x: 4, y: 0 });
+        assert_eq!(alignment.len(), 7);
+    }
+
+    #[test]
+    fn empty_sequences_work() {
+        let (x, y) = (b"", b"GATTACA");
+        let strategy = NeedlemanWunsch::new(1, -1, -1, -1);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy, |i, j| x[i] == y[j]).unwrap();
+        assert_eq!(set.global_score(), -7);
+        assert_eq!(set.global_alignment().len(), 7);
+    }
+
+    #[test]
+    fn banded_alignment_works() {
+        let x = b"ACGTACGTTAGCATGCATCGATCGATGCATGCTAGCTAGCATCGAT";
+        let y = b"ACGTACGTAGCATGCATCGATCGATGCATGCTAGCTTAGCATCGAT";
+        let strategy = NeedlemanWunsch::affine(1, -1, -2, -1);
+        let full: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy.clone(), |i, j| x[i] == y[j]).unwrap();
+        let band = Band::main_diagonal(3);
+        let set = AlignmentSet::banded(x.len(), y.len(), band, strategy, |i, j| x[i] == y[j]);
+        assert_eq!(set.global_score(), full.global_score());
+
+        let alignment = set.global_alignment();
+        let expected: Vec<Step> = full.global_alignment().steps().collect();
+        assert_eq!(alignment.steps().collect::<Vec<_>>(), expected);
+        assert!(!band.touches_edge(&alignment));
+    }
+
+    #[test]
+    fn narrow_band_touches_edge() {
+        let (x, y) = (b"TTTTGATTACA", b"GATTACA");
+        let strategy = NeedlemanWunsch::new(1, -1, -1, -1);
+        let band = Band::new(4, 1);
+        let set = AlignmentSet::banded(x.len(), y.len(), band, strategy.clone(), |i, j| {
+            x[i] == y[j]
+        });
+        assert!(band.touches_edge(&set.global_alignment()));
+
+        let band = Band::new(4, 0);
+        let set = AlignmentSet::banded(x.len(), y.len(), band, strategy, |i, j| x[i] == y[j]);
+        assert_eq!(set.global_score(), 3);
+        assert!(band.touches_edge(&set.global_alignment()));
+    }
+
+    #[test]
+    fn band_without_origin_works() {
+        let (x, y) = (b"GATTA", b"TTA");
+        let f = |i: usize, j: usize| x[i] == y[j];
+        for &(diagonal, radius) in &[(2, 0), (2, 1), (1, 1), (3, 1)] {
+            for (strategy, score) in [
+                (NeedlemanWunsch::new(1, -1, -1, -1), 1),
+                (NeedlemanWunsch::affine(1, -1, -2, -1), -1),
+            ] {
+                let band = Band::new(diagonal, radius);
+                let set = AlignmentSet::banded(x.len(), y.len(), band, strategy, f);
+                assert_eq!(set.global_score(), score);
+                assert_eq!(set.global_alignment().origin(), &Cursor { x: 0, y: 0 });
+            }
+        }
+    }
+
+    #[test]
+    fn fallible_variants_work() {
+        let (x, y) = (b"GATTACA", b"GCATGCU");
+        let strategy = NeedlemanWunsch::new(1, -1, -1, -1);
+        let f = |i: usize, j: usize| x[i] == y[j];
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::try_new(x.len(), y.len(), strategy.clone(), f).unwrap();
+        let alignment = set.try_global_alignment().unwrap();
+        assert_eq!(alignment.score(), set.global_score());
+        let alignment = set.try_local_alignment().unwrap();
+        assert_eq!(alignment.score(), set.local_score());
+
+        let result: Result<AlignmentSet<InMemoryAlignmentMatrix>, _> =
+            AlignmentSet::try_new(0, y.len(), strategy.clone(), f);
+        assert!(matches!(result, Err(Error::EmptySequence)));
+
+        let result: Result<AlignmentSet<InMemoryAlignmentMatrix>, _> =
+            AlignmentSet::try_new(usize::MAX / 2, 2, strategy, f);
+        assert!(matches!(result, Err(Error::SizeOverflow { .. })));
+    }
 }
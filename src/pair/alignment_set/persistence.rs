@@ -0,0 +1,200 @@
+use std::path::Path;
+
+use super::{AlignmentSet, Highscore, Highscores, Scratch};
+use crate::error::Error;
+use crate::pair::alignment_matrix::{
+    memory_mapped::AlignmentMatrix as MemoryMappedAlignmentMatrix, AlignmentMatrix,
+};
+use crate::pair::cursor::Cursor;
+use crate::pair::score::PersistableScore;
+use crate::pair::strategy::Strategy;
+use crate::pair::substitution::Substitution;
+
+// The matrix's metadata holds (with offsets relative to it):
+//
+// - `0`: whether the set was completely computed
+// - `1`: the score type's `PersistableScore::TAG`
+// - `2`: the strategy's free end gaps
+// - `3`: whether the strategy is local
+// - `8..56`: the strategy's match, mismatch, insert, delete,
+//   insert open and delete open scores (little-endian)
+// - `56..104`: the local and the global highscore, each as score, x and y (little-endian)
+const COMPLETE: usize = 0;
+const TAG: usize = 1;
+const END_GAPS: usize = 2;
+const LOCAL: usize = 3;
+const PARAMETERS: usize = 8;
+const HIGHSCORES: usize = 56;
+
+impl<N: PersistableScore> AlignmentSet<MemoryMappedAlignmentMatrix, N> {
+    /// Computes the alignment set of sequences of length `x_len` and `y_len`
+    /// into a file at `path`, which can be re-opened using `open()`.
+    pub fn create<P, S, F, R>(
+        path: P,
+        x_len: usize,
+        y_len: usize,
+        strategy: S,
+        f: F,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        S: Strategy<Score = N>,
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
+    {
+        let width = x_len.saturating_add(1);
+        let height = y_len.saturating_add(1);
+        let matrix = MemoryMappedAlignmentMatrix::create(path, width, height)?;
+        let mut set = Self::with_scratch(matrix, &strategy, f, &mut Scratch::default());
+
+        let highscores = [set.highscores.local, set.highscores.global];
+        let metadata = set.matrix.metadata_mut();
+        metadata[TAG] = N::TAG;
+        metadata[END_GAPS] = strategy.free_end_gaps().bits();
+        metadata[LOCAL] = u8::from(strategy.is_local());
+        for (index, score) in parameters(&strategy).iter().enumerate() {
+            write_u64(metadata, PARAMETERS + 8 * index, score.to_bits());
+        }
+        for (index, highscore) in highscores.iter().enumerate() {
+            let offset = HIGHSCORES + 24 * index;
+            write_u64(metadata, offset, highscore.score.to_bits());
+            write_u64(metadata, offset + 8, highscore.cursor.x as u64);
+            write_u64(metadata, offset + 16, highscore.cursor.y as u64);
+        }
+        metadata[COMPLETE] = 1;
+        set.matrix.flush()?;
+
+        Ok(set)
+    }
+
+    /// Re-opens a set previously computed using `create()`,
+    /// failing if it was computed with different parameters than those of `strategy`.
+    ///
+    /// Only the strategy's scores, free end gaps and whether it's local are checked.
+    pub fn open<P, S>(path: P, strategy: &S) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        S: Strategy<Score = N>,
+    {
+        let matrix = MemoryMappedAlignmentMatrix::open(path)?;
+        let metadata = matrix.metadata();
+        if metadata[COMPLETE] != 1 {
+            return Err(Error::InvalidHeader("incomplete alignment set"));
+        }
+        let is_same_strategy = (metadata[TAG] == N::TAG)
+            && (metadata[END_GAPS] == strategy.free_end_gaps().bits())
+            && (metadata[LOCAL] == u8::from(strategy.is_local()))
+            && parameters(strategy)
+                .iter()
+                .enumerate()
+                .all(|(index, score)| {
+                    read_u64(metadata, PARAMETERS + 8 * index) == score.to_bits()
+                });
+        if !is_same_strategy {
+            return Err(Error::StrategyMismatch);
+        }
+
+        let read_highscore = |offset: usize| -> Result<Highscore<N>, Error> {
+            let score = N::from_bits(read_u64(metadata, offset));
+            let x = read_u64(metadata, offset + 8) as usize;
+            let y = read_u64(metadata, offset + 16) as usize;
+            if x >= matrix.width() || y >= matrix.height() {
+                return Err(Error::InvalidHeader("highscore out of bounds"));
+            }
+            let cursor = Cursor { x, y };
+            Ok(Highscore { score, cursor })
+        };
+        let highscores = Highscores {
+            local: read_highscore(HIGHSCORES)?,
+            global: read_highscore(HIGHSCORES + 24)?,
+        };
+
+        Ok(AlignmentSet { matrix, highscores })
+    }
+}
+
+fn parameters<S: Strategy>(strategy: &S) -> [S::Score; 6] {
+    [
+        strategy.match_score(),
+        strategy.mismatch_score(),
+        strategy.insert_score(),
+        strategy.delete_score(),
+        strategy.insert_open_score(),
+        strategy.delete_open_score(),
+    ]
+}
+
+fn write_u64(bytes: &mut [u8], offset: usize, value: u64) {
+    bytes[offset..(offset + 8)].copy_from_slice(&value.to_le_bytes());
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    let mut buffer = [0; 8];
+    buffer.copy_from_slice(&bytes[offset..(offset + 8)]);
+    u64::from_le_bytes(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{
+        EndGaps, InMemoryAlignmentMatrix, NeedlemanWunsch, SemiGlobal, SmithWaterman,
+    };
+
+    #[test]
+    fn create_and_open_work() {
+        let (x, y) = (b"ACCGTTTGATTACAGGGG", b"CCGTAGTTACTTCAGGGGCCA");
+        let f = |i: usize, j: usize| x[i] == y[j];
+        let strategy = SemiGlobal::new(NeedlemanWunsch::affine(1, -1, -3, -1), EndGaps::all());
+        let expected: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy.clone(), f).unwrap();
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("set.seal");
+        AlignmentSet::create(&path, x.len(), y.len(), strategy.clone(), f).unwrap();
+
+        {
+            let set = AlignmentSet::open(&path, &strategy).unwrap();
+            assert_eq!(set.global_score(), expected.global_score());
+            assert_eq!(set.local_max(), expected.local_max());
+            let cigars: Vec<String> = set
+                .global_alignments()
+                .map(|a| a.cigar(y.len()).to_string())
+                .collect();
+            let expected_cigars: Vec<String> = expected
+                .global_alignments()
+                .map(|a| a.cigar(y.len()).to_string())
+                .collect();
+            assert_eq!(cigars, expected_cigars);
+        }
+
+        let other = SmithWaterman::new(2, -1, -1, -1);
+        let result = AlignmentSet::open(&path, &other);
+        assert!(matches!(result, Err(Error::StrategyMismatch)));
+
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        let len = file.metadata().unwrap().len();
+        file.set_len(len - 1).unwrap();
+        let result = AlignmentSet::open(&path, &strategy);
+        assert!(matches!(result, Err(Error::InvalidHeader(_))));
+
+        std::fs::write(&path, b"not a matrix").unwrap();
+        let result = AlignmentSet::open(&path, &strategy);
+        assert!(matches!(result, Err(Error::InvalidHeader(_))));
+    }
+
+    #[test]
+    fn strategy_kinds_mismatch() {
+        let (x, y) = (b"GATTACA", b"GCATGCU");
+        let f = |i: usize, j: usize| x[i] == y[j];
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("set.seal");
+        let global = NeedlemanWunsch::new(2, -1, -1, -1);
+        AlignmentSet::create(&path, x.len(), y.len(), global.clone(), f).unwrap();
+
+        let local = SmithWaterman::new(2, -1, -1, -1);
+        let result = AlignmentSet::open(&path, &local);
+        assert!(matches!(result, Err(Error::StrategyMismatch)));
+        assert!(AlignmentSet::open(&path, &global).is_ok());
+    }
+}
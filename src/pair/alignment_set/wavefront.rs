@@ -0,0 +1,382 @@
+use std::ops::Range;
+
+use rayon::prelude::*;
+
+use super::{AlignmentSet, Highscore, Highscores, Scratch};
+use crate::pair::alignment_matrix::AlignmentMatrix;
+use crate::pair::cursor::Cursor;
+use crate::pair::score::Score;
+use crate::pair::step_mask::StepMask;
+use crate::pair::strategy::Strategy;
+use crate::pair::substitution::Substitution;
+
+// The width and height of the tiles computed in parallel.
+const TILE_SIZE: usize = 256;
+
+#[derive(Clone)]
+struct Tile {
+    row: usize,
+    column: usize,
+    x: Range<usize>,
+    y: Range<usize>,
+}
+
+struct TileOutput<N> {
+    // The step masks of the tile's cells, in row-major order.
+    steps: Vec<StepMask>,
+    // The (overall, insertion) scores of the tile's bottom row.
+    bottom: Vec<(N, N)>,
+    // The (overall, deletion) scores of the tile's right column,
+    // starting with the overall score of the cell above it.
+    right: Vec<(N, N)>,
+    highscore: Option<Highscore<N>>,
+}
+
+// Shared, read-only state of a fill.
+struct Context<'a, S, F> {
+    strategy: &'a S,
+    f: &'a F,
+    columns: &'a [Range<usize>],
+    affine: bool,
+}
+
+impl<T, E, N> AlignmentSet<T, N>
+where
+    T: AlignmentMatrix<Error = E>,
+    N: Score + Send + Sync,
+{
+    /// Like `new()`, but computes tiles of the matrix in parallel,
+    /// proceeding along anti-diagonals of tiles.
+    ///
+    /// The resulting matrix and scores are identical to those of `new()`.
+    pub fn par_new<S, F, R>(
+        x_len: usize,
+        y_len: usize,
+        strategy: S,
+        f: F,
+    ) -> Result<AlignmentSet<T, N>, E>
+    where
+        S: Strategy<Score = N> + Sync,
+        F: Fn(usize, usize) -> R + Sync,
+        R: Substitution<N>,
+    {
+        let matrix = T::new(x_len + 1, y_len + 1)?;
+        Ok(Self::par_with_matrix(matrix, strategy, f))
+    }
+
+    /// Like `with_matrix()`, but computes tiles of the matrix in parallel.
+    pub fn par_with_matrix<S, F, R>(mut matrix: T, strategy: S, f: F) -> AlignmentSet<T, N>
+    where
+        S: Strategy<Score = N> + Sync,
+        F: Fn(usize, usize) -> R + Sync,
+        R: Substitution<N>,
+    {
+        let highscores = Self::fill_wavefront(&mut matrix, &strategy, &f, TILE_SIZE);
+        AlignmentSet { matrix, highscores }
+    }
+
+    fn fill_wavefront<S, F, R>(
+        matrix: &mut T,
+        strategy: &S,
+        f: &F,
+        tile_size: usize,
+    ) -> Highscores<N>
+    where
+        S: Strategy<Score = N> + Sync,
+        F: Fn(usize, usize) -> R + Sync,
+        R: Substitution<N>,
+    {
+        let (width, height) = (matrix.width(), matrix.height());
+        if (width < 2) || (height < 2) {
+            let scratch = &mut Scratch::default();
+            return if strategy.has_affine_gaps() {
+                Self::fill_affine(matrix, strategy, f, scratch)
+            } else {
+                Self::fill_linear(matrix, strategy, f, scratch)
+            };
+        }
+
+        Self::prepare_matrix(matrix, strategy);
+        let mut first_row = vec![];
+        Self::prepare_row(&mut first_row, matrix, strategy);
+        let columns: Vec<Range<usize>> = (0..height).map(|y| matrix.columns(y)).collect();
+        let context = Context {
+            strategy,
+            f,
+            columns: &columns,
+            affine: strategy.has_affine_gaps(),
+        };
+
+        let tile_ranges = |len: usize| -> Vec<Range<usize>> {
+            (1..len)
+                .step_by(tile_size)
+                .map(|start| start..(start + tile_size).min(len))
+                .collect()
+        };
+        let x_ranges = tile_ranges(width);
+        let y_ranges = tile_ranges(height);
+
+        let mut top: Vec<Vec<(N, N)>> = x_ranges
+            .iter()
+            .map(|x| x.clone().map(|x| (first_row[x], N::MIN)).collect())
+            .collect();
+        let mut left: Vec<Vec<(N, N)>> = y_ranges
+            .iter()
+            .map(|y| {
+                ((y.start - 1)..y.end)
+                    .map(|y| match y {
+                        0 => (first_row[0], N::MIN),
+                        // The first column is always reachable, just like in the serial fill:
+                        y => (Self::leading_insert_score(strategy, y), N::MIN),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut highscores = Self::prepared_highscores();
+        let mut last_column = vec![first_row[width - 1]; height];
+
+        for diagonal in 0..(x_ranges.len() + y_ranges.len() - 1) {
+            let tiles: Vec<Tile> = (0..y_ranges.len())
+                .filter(|&row| (row <= diagonal) && (diagonal - row < x_ranges.len()))
+                .map(|row| {
+                    let column = diagonal - row;
+                    Tile {
+                        row,
+                        column,
+                        x: x_ranges[column].clone(),
+                        y: y_ranges[row].clone(),
+                    }
+                })
+                .collect();
+
+            let outputs: Vec<TileOutput<N>> = tiles
+                .par_iter()
+                .map(|tile| Self::fill_tile(&context, tile, &top[tile.column], &left[tile.row]))
+                .collect();
+
+            for (tile, output) in tiles.into_iter().zip(outputs) {
+                let mut steps = output.steps.into_iter();
+                for y in tile.y.clone() {
+                    for x in tile.x.clone() {
+                        matrix.set_at(&Cursor { x, y }, steps.next().unwrap());
+                    }
+                }
+                if let Some(highscore) = output.highscore {
+                    Self::merge_highscore(&mut highscores, highscore);
+                }
+                if tile.x.end == width {
+                    for (y, &(score, _)) in tile.y.clone().zip(&output.right[1..]) {
+                        last_column[y] = score;
+                    }
+                }
+                top[tile.column] = output.bottom;
+                left[tile.row] = output.right;
+            }
+        }
+
+        let y_len = height - 1;
+        let mut last_row = vec![Self::leading_insert_score(strategy, y_len)];
+        last_row.extend(top.iter().flatten().map(|&(score, _)| score));
+
+        let mut last_column_highscore = Self::prepared_last_column(&first_row);
+        for (y, &score) in last_column.iter().enumerate().skip(1) {
+            if score > last_column_highscore.score {
+                let cursor = Cursor { x: width - 1, y };
+                last_column_highscore = Highscore { score, cursor };
+            }
+        }
+
+        highscores.global =
+            Self::global_highscore(strategy, &last_row, last_column_highscore, y_len);
+        highscores
+    }
+
+    fn fill_tile<S, F, R>(
+        context: &Context<'_, S, F>,
+        tile: &Tile,
+        top: &[(N, N)],
+        left: &[(N, N)],
+    ) -> TileOutput<N>
+    where
+        S: Strategy<Score = N>,
+        F: Fn(usize, usize) -> R,
+        R: Substitution<N>,
+    {
+        let strategy = context.strategy;
+        let mut steps = Vec::with_capacity(tile.x.len() * tile.y.len());
+        let mut row: Vec<N> = top.iter().map(|&(score, _)| score).collect();
+        let mut insert_row: Vec<N> = top.iter().map(|&(_, insert)| insert).collect();
+        let mut right = Vec::with_capacity(left.len());
+        right.push((*row.last().unwrap(), N::MIN));
+        let mut highscore: Option<Highscore<N>> = None;
+
+        let mut corner = left[0].0;
+        for (y, &(left_score, left_delete)) in tile.y.clone().zip(&left[1..]) {
+            let columns = &context.columns[y];
+            let mut last_diagonal = corner;
+            let (mut previous, mut delete) = (left_score, left_delete);
+            corner = left_score;
+            for (i, x) in tile.x.clone().enumerate() {
+                if !columns.contains(&x) {
+                    steps.push(StepMask::STOP);
+                    last_diagonal = row[i];
+                    row[i] = N::MIN;
+                    insert_row[i] = N::MIN;
+                    previous = N::MIN;
+                    delete = N::MIN;
+                    continue;
+                }
+                let substitution = (context.f)(x - 1, y - 1).score(strategy);
+                let (mask, score) = if context.affine {
+                    let align = last_diagonal.saturating_add(substitution);
+                    let up = (row[i], insert_row[i]);
+                    let (mask, score, insert, deleted) =
+                        Self::calculate_affine_cell(strategy, align, up, (previous, delete));
+                    insert_row[i] = insert;
+                    delete = deleted;
+                    (mask, score)
+                } else {
+                    let scores = (last_diagonal, previous, row[i]);
+                    Self::calculate_cell(strategy, scores, substitution)
+                };
+                steps.push(mask);
+                let is_better = match highscore {
+                    Some(highscore) => score >= highscore.score,
+                    None => true,
+                };
+                if is_better {
+                    let cursor = Cursor { x, y };
+                    highscore = Some(Highscore { score, cursor });
+                }
+                last_diagonal = row[i];
+                row[i] = score;
+                previous = score;
+            }
+            right.push((previous, delete));
+        }
+
+        let bottom = row.into_iter().zip(insert_row).collect();
+        TileOutput {
+            steps,
+            bottom,
+            right,
+            highscore,
+        }
+    }
+
+    // Keeps the best local score, resolving ties in favor of
+    // the cell that comes last in row-major order, just like the serial fill.
+    fn merge_highscore(highscores: &mut Highscores<N>, highscore: Highscore<N>) {
+        let local = &highscores.local;
+        let later = (highscore.cursor.y, highscore.cursor.x) > (local.cursor.y, local.cursor.x);
+        if (highscore.score > local.score) || ((highscore.score == local.score) && later) {
+            highscores.local = highscore;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::test_utils::sequence;
+    use crate::pair::{
+        Band, BandedAlignmentMatrix, EndGaps, InMemoryAlignmentMatrix, MemoryMappedAlignmentMatrix,
+        NeedlemanWunsch, SemiGlobal, SmithWaterman,
+    };
+
+    fn assert_matches_serial<T, S>(x: &[u8], y: &[u8], strategy: S)
+    where
+        T: AlignmentMatrix,
+        T::Error: std::fmt::Debug,
+        S: Strategy<Score = isize> + Clone + Sync,
+    {
+        let f = |i: usize, j: usize| x[i] == y[j];
+        let serial: AlignmentSet<T> =
+            AlignmentSet::new(x.len(), y.len(), strategy.clone(), f).unwrap();
+        for tile_size in [1, 7, 64].iter().copied() {
+            let mut matrix = T::new(x.len() + 1, y.len() + 1).unwrap();
+            let highscores =
+                AlignmentSet::<T>::fill_wavefront(&mut matrix, &strategy, &f, tile_size);
+            let parallel = AlignmentSet { matrix, highscores };
+            assert_eq!(parallel.local_score(), serial.local_score());
+            assert_eq!(parallel.local_max(), serial.local_max());
+            assert_eq!(parallel.global_score(), serial.global_score());
+            assert_eq!(parallel.global_max(), serial.global_max());
+            for y in 0..=y.len() {
+                for x in 0..=x.len() {
+                    let cursor = Cursor { x, y };
+                    assert_eq!(parallel.matrix().at(&cursor), serial.matrix().at(&cursor));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wavefront_matches_serial() {
+        let (x, y) = (sequence(150, 1, b"ACGT"), sequence(130, 2, b"ACGT"));
+        assert_matches_serial::<InMemoryAlignmentMatrix, _>(
+            &x,
+            &y,
+            NeedlemanWunsch::new(1, -1, -1, -1),
+        );
+        assert_matches_serial::<InMemoryAlignmentMatrix, _>(
+            &x,
+            &y,
+            SmithWaterman::affine(2, -1, -3, -1),
+        );
+        let end_gaps = EndGaps::X_LEADING | EndGaps::Y_TRAILING;
+        assert_matches_serial::<MemoryMappedAlignmentMatrix, _>(
+            &x,
+            &y,
+            SemiGlobal::new(NeedlemanWunsch::affine(1, -1, -2, -1), end_gaps),
+        );
+        assert_matches_serial::<InMemoryAlignmentMatrix, _>(
+            &x[..1],
+            &y,
+            NeedlemanWunsch::new(1, -1, -1, -1),
+        );
+    }
+
+    #[test]
+    fn wavefront_respects_band() {
+        let (x, y) = (sequence(90, 3, b"ACGT"), sequence(100, 4, b"ACGT"));
+        let f = |i: usize, j: usize| x[i] == y[j];
+        let band = Band::new(-5, 8);
+        let strategy = NeedlemanWunsch::affine(1, -1, -2, -1);
+        let serial = AlignmentSet::banded(x.len(), y.len(), band, strategy.clone(), f);
+        let mut matrix = BandedAlignmentMatrix::with_band(x.len() + 1, y.len() + 1, band);
+        let highscores = AlignmentSet::fill_wavefront(&mut matrix, &strategy, &f, 16);
+        let parallel = AlignmentSet { matrix, highscores };
+        assert_eq!(parallel.global_score(), serial.global_score());
+        assert_eq!(parallel.local_max(), serial.local_max());
+        let steps: Vec<_> = parallel.global_alignment().steps().collect();
+        let expected: Vec<_> = serial.global_alignment().steps().collect();
+        assert_eq!(steps, expected);
+    }
+
+    #[test]
+    fn wavefront_reaches_first_column_outside_band() {
+        let (x, y) = (b"ACGTACGTAA", b"ACGTACGTAAAAAAAA");
+        let f = |i: usize, j: usize| x[i] == y[j];
+        let strategy = SemiGlobal::new(NeedlemanWunsch::affine(1, -1, -5, -1), EndGaps::X_TRAILING);
+        for &(diagonal, radius) in &[(0, 1), (0, 2), (-2, 1), (-3, 2)] {
+            let band = Band::new(diagonal, radius);
+            let (width, height) = (x.len() + 1, y.len() + 1);
+            let matrix = BandedAlignmentMatrix::with_band(width, height, band);
+            let serial: AlignmentSet<_, i32> =
+                AlignmentSet::with_matrix(matrix, strategy.clone(), f);
+            let matrix = BandedAlignmentMatrix::with_band(width, height, band);
+            let parallel: AlignmentSet<_, i32> =
+                AlignmentSet::par_with_matrix(matrix, strategy.clone(), f);
+            assert_eq!(parallel.global_score(), serial.global_score());
+            assert_eq!(parallel.global_max(), serial.global_max());
+            for y in 0..height {
+                for x in 0..width {
+                    let cursor = Cursor { x, y };
+                    assert_eq!(parallel.matrix().at(&cursor), serial.matrix().at(&cursor));
+                }
+            }
+        }
+    }
+}
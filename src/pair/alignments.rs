@@ -1,21 +1,22 @@
 use crate::pair::alignment::Alignment;
 use crate::pair::alignment_matrix::AlignmentMatrix;
 use crate::pair::cursor::Cursor;
+use crate::pair::score::Score;
 use crate::pair::step_mask::StepMask;
 
-pub struct Alignments<'a, T: 'a> {
+pub struct Alignments<'a, T: 'a, N = isize> {
     matrix: &'a T,
     stack: Vec<(StepMask, Cursor, usize)>,
     steps: Vec<StepMask>,
-    score: isize,
+    score: N,
 }
 
-impl<'a, T> Alignments<'a, T> {
+impl<'a, T, N> Alignments<'a, T, N> {
     pub fn new(
         matrix: &'a T,
         stack: Vec<(StepMask, Cursor, usize)>,
         steps: Vec<StepMask>,
-        score: isize,
+        score: N,
     ) -> Self {
         Alignments {
             matrix,
@@ -26,49 +27,83 @@ impl<'a, T> Alignments<'a, T> {
     }
 }
 
-impl<'a, T> Alignments<'a, T>
+impl<'a, T, N> Alignments<'a, T, N>
 where
     T: AlignmentMatrix,
 {
-    fn branches(&self, cursor: Cursor) -> Vec<(StepMask, Cursor)> {
+    // The step mask a cursor was reached with doubles as the traceback state:
+    // For affine gaps a set `*_EXTEND` flag means that the gap may still be
+    // extended from here, while otherwise we are back on the main path.
+    fn branches(&self, step_mask: StepMask, cursor: Cursor) -> Vec<(StepMask, Cursor)> {
         let steps = self.matrix.at(&cursor);
         let mut branches = vec![];
-        if steps == StepMask::STOP {
+        if step_mask.contains(StepMask::INSERT_EXTEND) {
+            Self::gap_branches(steps, cursor, StepMask::INSERT, &mut branches);
+            return branches;
+        }
+        if step_mask.contains(StepMask::DELETE_EXTEND) {
+            Self::gap_branches(steps, cursor, StepMask::DELETE, &mut branches);
+            return branches;
+        }
+        if steps.direction() == StepMask::STOP {
             return branches;
         }
         for mask in [StepMask::ALIGN, StepMask::INSERT, StepMask::DELETE].iter() {
             if steps.contains(*mask) {
-                let mut branch = cursor;
-                branch.apply_backwards_step(*mask);
-                branches.push((*mask, branch));
+                Self::gap_branches(steps, cursor, *mask, &mut branches);
             }
         }
         branches
     }
+
+    fn gap_branches(
+        steps: StepMask,
+        cursor: Cursor,
+        mask: StepMask,
+        branches: &mut Vec<(StepMask, Cursor)>,
+    ) {
+        let (extend, open) = match mask {
+            StepMask::INSERT => (StepMask::INSERT_EXTEND, StepMask::INSERT_OPEN),
+            StepMask::DELETE => (StepMask::DELETE_EXTEND, StepMask::DELETE_OPEN),
+            _ => (StepMask::empty(), StepMask::empty()),
+        };
+        let mut branch = cursor;
+        branch.apply_backwards_step(mask);
+        if !steps.intersects(extend | open) {
+            branches.push((mask, branch));
+            return;
+        }
+        if steps.contains(open) {
+            branches.push((mask, branch));
+        }
+        if steps.contains(extend) {
+            branches.push((mask | extend, branch));
+        }
+    }
 }
 
-impl<'a, T> Iterator for Alignments<'a, T>
+impl<'a, T, N> Iterator for Alignments<'a, T, N>
 where
     T: AlignmentMatrix,
+    N: Score,
 {
-    type Item = Alignment;
+    type Item = Alignment<N>;
 
-    fn next(&mut self) -> Option<Alignment> {
-        let zero = Cursor { x: 0, y: 0 };
+    fn next(&mut self) -> Option<Alignment<N>> {
         while let Some((step_mask, cursor, depth)) = self.stack.pop() {
             if step_mask != StepMask::STOP {
                 self.steps.truncate(depth - 1);
-                self.steps.push(step_mask);
-            }
-            let branches = self.branches(cursor);
-            for (step_mask, cursor) in branches {
-                self.stack.push((step_mask, cursor, depth + 1));
+                self.steps.push(step_mask.direction());
             }
-            if ((step_mask == StepMask::STOP) && (depth > 0)) || (cursor == zero) {
+            let branches = self.branches(step_mask, cursor);
+            if branches.is_empty() {
                 let mut steps: Vec<StepMask> = self.steps.clone();
                 steps.reverse();
                 return Some(Alignment::new(cursor, steps, self.score));
             }
+            for (step_mask, cursor) in branches {
+                self.stack.push((step_mask, cursor, depth + 1));
+            }
         }
         None
     }
@@ -0,0 +1,90 @@
+use std::{cmp, ops::Range};
+
+use crate::pair::alignment::Alignment;
+use crate::pair::cursor::Cursor;
+use crate::pair::score::Score;
+
+/// A band of cells within `radius` of a diagonal of the alignment matrix.
+///
+/// A cell's diagonal is given by `x - y`, with `0` being the main diagonal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Band {
+    pub diagonal: isize,
+    pub radius: usize,
+}
+
+impl Band {
+    pub fn new(diagonal: isize, radius: usize) -> Band {
+        Band { diagonal, radius }
+    }
+
+    /// A band of the given `radius` around the main diagonal.
+    pub fn main_diagonal(radius: usize) -> Band {
+        Band::new(0, radius)
+    }
+
+    /// Whether `cursor` lies within the band.
+    pub fn contains(&self, cursor: &Cursor) -> bool {
+        self.distance(cursor) <= self.radius
+    }
+
+    /// Whether `cursor` lies on the outermost diagonals of the band.
+    pub fn is_edge(&self, cursor: &Cursor) -> bool {
+        self.distance(cursor) == self.radius
+    }
+
+    /// The columns of row `y` that lie within the band, clamped to `width`.
+    pub fn columns(&self, y: usize, width: usize) -> Range<usize> {
+        let center = (y as isize) + self.diagonal;
+        let radius = self.radius as isize;
+        let start = cmp::max(center.saturating_sub(radius), 0) as usize;
+        let end = cmp::max(center.saturating_add(radius).saturating_add(1), 0) as usize;
+        cmp::min(start, width)..cmp::min(end, width)
+    }
+
+    /// Whether any cell on the path of `alignment` lies on the edge of the band,
+    /// in which case a wider band might yield a better alignment.
+    pub fn touches_edge<N: Score>(&self, alignment: &Alignment<N>) -> bool {
+        let mut cursor = *alignment.origin();
+        if self.is_edge(&cursor) {
+            return true;
+        }
+        alignment.steps().any(|step| {
+            cursor.apply_forwards_step(step.mask());
+            self.is_edge(&cursor)
+        })
+    }
+
+    fn distance(&self, cursor: &Cursor) -> usize {
+        let diagonal = (cursor.x as isize) - (cursor.y as isize);
+        (diagonal - self.diagonal).unsigned_abs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_work() {
+        let band = Band::new(2, 1);
+        assert_eq!(band.columns(0, 10), 1..4);
+        assert_eq!(band.columns(5, 10), 6..9);
+        assert_eq!(band.columns(8, 10), 9..10);
+        assert_eq!(band.columns(9, 10), 10..10);
+
+        let band = Band::new(-3, 1);
+        assert_eq!(band.columns(0, 10), 0..0);
+        assert_eq!(band.columns(2, 10), 0..1);
+        assert_eq!(band.columns(4, 10), 0..3);
+    }
+
+    #[test]
+    fn edge_works() {
+        let band = Band::main_diagonal(2);
+        assert!(band.contains(&Cursor { x: 3, y: 1 }));
+        assert!(band.is_edge(&Cursor { x: 3, y: 1 }));
+        assert!(!band.is_edge(&Cursor { x: 2, y: 1 }));
+        assert!(!band.contains(&Cursor { x: 0, y: 3 }));
+    }
+}
@@ -0,0 +1,217 @@
+use std::marker::PhantomData;
+
+use rayon::prelude::*;
+
+use crate::error::Error;
+use crate::pair::alignment::Alignment;
+use crate::pair::alignment_matrix::AlignmentMatrix;
+use crate::pair::alignment_scores::AlignmentScores;
+use crate::pair::alignment_set::{AlignmentSet, Scratch};
+use crate::pair::strategy::Strategy;
+use crate::pair::substitution::Substitution;
+
+/// Whether to trace back from the best local or global score.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Scope {
+    Local,
+    Global,
+}
+
+/// The kind of result to compute for a pair of sequences.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResultKind {
+    /// Only the scores, without allocating a traceback matrix.
+    Scores,
+    /// The first optimal alignment.
+    Alignment(Scope),
+    /// All optimal alignments.
+    Alignments(Scope),
+}
+
+#[derive(Debug)]
+pub enum BatchResult<N = isize> {
+    Scores(AlignmentScores<N>),
+    Alignment(Alignment<N>),
+    Alignments(Vec<Alignment<N>>),
+}
+
+// Per-thread buffers, reused across pairs.
+struct BatchScratch<M, N> {
+    matrix: Option<M>,
+    scratch: Scratch<N>,
+}
+
+/// Aligns many independent pairs of sequences in parallel,
+/// using matrices of type `M` for tracebacks.
+///
+/// Each thread reuses its score buffers and matrix allocation across pairs.
+pub struct Batch<M, S, F> {
+    strategy: S,
+    f: F,
+    matrix: PhantomData<fn() -> M>,
+}
+
+impl<M, S, F> Batch<M, S, F>
+where
+    M: AlignmentMatrix + Send,
+    M::Error: Send,
+    S: Strategy + Sync,
+    S::Score: Send + Sync,
+{
+    /// Creates a batch aligner comparing symbols using `f`.
+    ///
+    /// Panics if `strategy` isn't supported by `M`, see `try_new()`.
+    pub fn new(strategy: S, f: F) -> Batch<M, S, F> {
+        match Self::try_new(strategy, f) {
+            Ok(batch) => batch,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Fails with `Error::UnsupportedStrategy` for affine gap scores
+    /// if `M` doesn't store affine gap flags.
+    pub fn try_new(strategy: S, f: F) -> Result<Batch<M, S, F>, Error> {
+        if strategy.has_affine_gaps() && !M::STORES_AFFINE_FLAGS {
+            return Err(Error::UnsupportedStrategy(
+                "affine gaps require a matrix storing affine gap flags",
+            ));
+        }
+        Ok(Batch {
+            strategy,
+            f,
+            matrix: PhantomData,
+        })
+    }
+
+    /// Aligns each `(x, y, kind)` pair, returning the results in input order.
+    #[allow(clippy::type_complexity)]
+    pub fn align<I, X, Y, T, R>(&self, pairs: I) -> Vec<Result<BatchResult<S::Score>, M::Error>>
+    where
+        I: IntoIterator<Item = (X, Y, ResultKind)>,
+        X: AsRef<[T]> + Sync,
+        Y: AsRef<[T]> + Sync,
+        F: Fn(&T, &T) -> R + Sync,
+        R: Substitution<S::Score>,
+    {
+        let pairs: Vec<(X, Y, ResultKind)> = pairs.into_iter().collect();
+        pairs
+            .par_iter()
+            .map_init(
+                || BatchScratch {
+                    matrix: None,
+                    scratch: Scratch::default(),
+                },
+                |scratch, (x, y, kind)| self.align_pair(scratch, x.as_ref(), y.as_ref(), *kind),
+            )
+            .collect()
+    }
+
+    fn align_pair<T, R>(
+        &self,
+        scratch: &mut BatchScratch<M, S::Score>,
+        x: &[T],
+        y: &[T],
+        kind: ResultKind,
+    ) -> Result<BatchResult<S::Score>, M::Error>
+    where
+        F: Fn(&T, &T) -> R,
+        R: Substitution<S::Score>,
+    {
+        let f = |i: usize, j: usize| (self.f)(&x[i], &y[j]);
+        let strategy = &self.strategy;
+        let (scope, all) = match kind {
+            ResultKind::Scores => {
+                let scratch = &mut scratch.scratch;
+                let scores = AlignmentScores::with_scratch(x.len(), y.len(), strategy, f, scratch);
+                return Ok(BatchResult::Scores(scores));
+            }
+            ResultKind::Alignment(scope) => (scope, false),
+            ResultKind::Alignments(scope) => (scope, true),
+        };
+
+        let (width, height) = (x.len() + 1, y.len() + 1);
+        let matrix = match scratch.matrix.take() {
+            Some(mut matrix) => {
+                matrix.resize(width, height)?;
+                matrix
+            }
+            None => M::new(width, height)?,
+        };
+        let set = AlignmentSet::with_scratch(matrix, strategy, f, &mut scratch.scratch);
+        let result = {
+            let mut alignments = match scope {
+                Scope::Local => set.local_alignments(),
+                Scope::Global => set.global_alignments(),
+            };
+            if all {
+                BatchResult::Alignments(alignments.collect())
+            } else {
+                BatchResult::Alignment(alignments.next().unwrap())
+            }
+        };
+        scratch.matrix = Some(set.into_matrix());
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{InMemoryAlignmentMatrix, NeedlemanWunsch, PackedAlignmentMatrix, Step};
+
+    #[test]
+    fn batch_works() {
+        let strategy = NeedlemanWunsch::new(1, -1, -1, -1);
+        let batch: Batch<InMemoryAlignmentMatrix, _, _> =
+            Batch::new(strategy.clone(), |a: &u8, b: &u8| a == b);
+        let sequences: Vec<(&[u8], &[u8])> = vec![
+            (b"GATTACA", b"GCATGCA"),
+            (b"AAAAAAAAAAAAAAAAAAAAAAAAAAAAA", b"AAAA"),
+            (b"", b"ACGT"),
+            (b"ACGT", b"ACGT"),
+        ];
+        let kinds = [
+            ResultKind::Scores,
+            ResultKind::Alignment(Scope::Global),
+            ResultKind::Alignments(Scope::Global),
+        ];
+        let pairs: Vec<_> = (0..100)
+            .map(|i| {
+                let (x, y) = sequences[i % sequences.len()];
+                (x, y, kinds[i % kinds.len()])
+            })
+            .collect();
+
+        let results = batch.align(pairs.iter().copied());
+        assert_eq!(results.len(), pairs.len());
+        for ((x, y, kind), result) in pairs.into_iter().zip(results) {
+            let set: AlignmentSet<InMemoryAlignmentMatrix> =
+                AlignmentSet::new(x.len(), y.len(), strategy.clone(), |i, j| x[i] == y[j]).unwrap();
+            match (kind, result.unwrap()) {
+                (ResultKind::Scores, BatchResult::Scores(scores)) => {
+                    assert_eq!(scores.global_score(), set.global_score());
+                }
+                (ResultKind::Alignment(_), BatchResult::Alignment(alignment)) => {
+                    let steps: Vec<Step> = alignment.steps().collect();
+                    let expected: Vec<Step> = set.global_alignment().steps().collect();
+                    assert_eq!(steps, expected);
+                }
+                (ResultKind::Alignments(_), BatchResult::Alignments(alignments)) => {
+                    assert_eq!(alignments.len(), set.global_alignments().count());
+                }
+                _ => panic!("Unexpected result kind."),
+            }
+        }
+    }
+
+    #[test]
+    fn affine_batches_need_affine_flags() {
+        let strategy = NeedlemanWunsch::affine(1, -1, -3, -1);
+        let f = |a: &u8, b: &u8| a == b;
+        let result: Result<Batch<PackedAlignmentMatrix, _, _>, _> =
+            Batch::try_new(strategy.clone(), f);
+        assert!(matches!(result, Err(Error::UnsupportedStrategy(_))));
+        let result: Result<Batch<PackedAlignmentMatrix<7>, _, _>, _> = Batch::try_new(strategy, f);
+        assert!(result.is_ok());
+    }
+}
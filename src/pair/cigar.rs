@@ -0,0 +1,415 @@
+use std::{error, fmt, str::FromStr};
+
+use crate::pair::alignment::Alignment;
+use crate::pair::cursor::Cursor;
+use crate::pair::run::Run;
+use crate::pair::score::Score;
+
+/// A CIGAR operation, treating `x` as the reference and `y` as the query.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CigarOp {
+    /// `M`: an alignment match, which may be a sequence match or mismatch.
+    Match,
+    /// `I`: an insertion into the reference, consuming `y`.
+    Insertion,
+    /// `D`: a deletion from the reference, consuming `x`.
+    Deletion,
+    /// `N`: a skipped region of the reference (such as an intron), consuming `x`.
+    Skip,
+    /// `S`: a soft clip, skipping unaligned symbols of `y`.
+    SoftClip,
+    /// `H`: a hard clip, for symbols not present in `y`.
+    HardClip,
+    /// `=`: a sequence match.
+    Equal,
+    /// `X`: a sequence mismatch.
+    Mismatch,
+    /// `P`: padding, consuming neither `x` nor `y`.
+    Padding,
+}
+
+impl CigarOp {
+    pub fn to_char(self) -> char {
+        match self {
+            CigarOp::Match => 'M',
+            CigarOp::Insertion => 'I',
+            CigarOp::Deletion => 'D',
+            CigarOp::Skip => 'N',
+            CigarOp::SoftClip => 'S',
+            CigarOp::HardClip => 'H',
+            CigarOp::Equal => '=',
+            CigarOp::Mismatch => 'X',
+            CigarOp::Padding => 'P',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<CigarOp> {
+        match c {
+            'M' => Some(CigarOp::Match),
+            'I' => Some(CigarOp::Insertion),
+            'D' => Some(CigarOp::Deletion),
+            'N' => Some(CigarOp::Skip),
+            'S' => Some(CigarOp::SoftClip),
+            'H' => Some(CigarOp::HardClip),
+            '=' => Some(CigarOp::Equal),
+            'X' => Some(CigarOp::Mismatch),
+            'P' => Some(CigarOp::Padding),
+            _ => None,
+        }
+    }
+
+    fn is_clip(self) -> bool {
+        matches!(self, CigarOp::SoftClip | CigarOp::HardClip)
+    }
+}
+
+/// A CIGAR string, as a sequence of `(length, operation)` pairs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cigar {
+    ops: Vec<(usize, CigarOp)>,
+}
+
+impl Cigar {
+    pub fn new() -> Cigar {
+        Cigar::default()
+    }
+
+    pub fn ops(&self) -> &[(usize, CigarOp)] {
+        &self.ops
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Appends `len` times `op`, merging it with the last operation if equal.
+    ///
+    /// Panics if `op` would misplace a clip, see `try_push()`.
+    pub fn push(&mut self, len: usize, op: CigarOp) {
+        if let Err(error) = self.try_push(len, op) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Like `push`, but failing with `ParseCigarError::MisplacedClip` if clips wouldn't
+    /// only occur at either end (with hard clips outermost), instead of panicking.
+    pub fn try_push(&mut self, len: usize, op: CigarOp) -> Result<(), ParseCigarError> {
+        if len == 0 {
+            return Ok(());
+        }
+        let last = self.ops.last().map(|&(_, op)| op);
+        if last == Some(op) {
+            self.ops.last_mut().unwrap().0 += len;
+            return Ok(());
+        }
+        // The clips at the end, which are trailing if preceded by any other operation:
+        let clips = self
+            .ops
+            .iter()
+            .rev()
+            .take_while(|(_, op)| op.is_clip())
+            .count();
+        let is_trailing = clips < self.ops.len();
+        let is_misplaced = match op {
+            CigarOp::HardClip => !is_trailing && (clips > 0),
+            CigarOp::SoftClip => last == Some(CigarOp::HardClip) && (is_trailing || (clips > 1)),
+            _ => is_trailing && (clips > 0),
+        };
+        if is_misplaced {
+            return Err(ParseCigarError::MisplacedClip);
+        }
+        self.ops.push((len, op));
+        Ok(())
+    }
+}
+
+impl fmt::Display for Cigar {
+    fn fmt(&self, form: &mut fmt::Formatter) -> fmt::Result {
+        if self.ops.is_empty() {
+            return write!(form, "*");
+        }
+        for (len, op) in &self.ops {
+            write!(form, "{}{}", len, op.to_char())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseCigarError {
+    /// An operation without a preceding length.
+    MissingLength,
+    /// A length that is zero or too large.
+    InvalidLength,
+    /// A length without a following operation.
+    MissingOperation,
+    /// An unknown operation character.
+    InvalidOperation(char),
+    /// A clip between non-clip operations.
+    MisplacedClip,
+}
+
+impl fmt::Display for ParseCigarError {
+    fn fmt(&self, form: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCigarError::MissingLength => write!(form, "missing CIGAR operation length"),
+            ParseCigarError::InvalidLength => write!(form, "invalid CIGAR operation length"),
+            ParseCigarError::MissingOperation => write!(form, "missing CIGAR operation"),
+            ParseCigarError::InvalidOperation(c) => write!(form, "invalid CIGAR operation {:?}", c),
+            ParseCigarError::MisplacedClip => write!(form, "CIGAR clip not at either end"),
+        }
+    }
+}
+
+impl error::Error for ParseCigarError {}
+
+impl FromStr for Cigar {
+    type Err = ParseCigarError;
+
+    fn from_str(s: &str) -> Result<Cigar, ParseCigarError> {
+        let mut cigar = Cigar::new();
+        if s == "*" {
+            return Ok(cigar);
+        }
+        let mut len: Option<usize> = None;
+        for c in s.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                let value = len.unwrap_or(0);
+                let value = value
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add(digit as usize));
+                len = Some(value.ok_or(ParseCigarError::InvalidLength)?);
+                continue;
+            }
+            let op = CigarOp::from_char(c).ok_or(ParseCigarError::InvalidOperation(c))?;
+            match len.take() {
+                None => return Err(ParseCigarError::MissingLength),
+                Some(0) => return Err(ParseCigarError::InvalidLength),
+                Some(len) => cigar.ops.push((len, op)),
+            }
+        }
+        if len.is_some() {
+            return Err(ParseCigarError::MissingOperation);
+        }
+
+        // Clips may only occur at either end, with hard clips outermost:
+        let ops = &cigar.ops;
+        let is_hard_clip = |&(_, op): &(usize, CigarOp)| op == CigarOp::HardClip;
+        for (i, &(_, op)) in ops.iter().enumerate() {
+            let is_valid = match op {
+                CigarOp::HardClip => (i == 0) || (i + 1 == ops.len()),
+                CigarOp::SoftClip => {
+                    ops[..i].iter().all(is_hard_clip) || ops[(i + 1)..].iter().all(is_hard_clip)
+                }
+                _ => true,
+            };
+            if !is_valid {
+                return Err(ParseCigarError::MisplacedClip);
+            }
+        }
+        Ok(cigar)
+    }
+}
+
+impl<N: Score> Alignment<N> {
+    /// Returns the alignment's CIGAR string using `M` for both matches and mismatches,
+    /// with `x` as the reference and `y` (of length `y_len`) as the query.
+    ///
+    /// Unaligned symbols of `y` at either end are soft clipped.
+    pub fn cigar(&self, y_len: usize) -> Cigar {
+        self.cigar_with(y_len, |_, _| CigarOp::Match)
+    }
+
+    /// Returns the alignment's CIGAR string using `=` for matches and `X` for mismatches,
+    /// with `x` as the reference and `y` as the query.
+    ///
+    /// Unaligned symbols of `y` at either end are soft clipped.
+    pub fn extended_cigar<T: PartialEq>(&self, x: &[T], y: &[T]) -> Cigar {
+        self.cigar_with(y.len(), |i, j| {
+            if x[i] == y[j] {
+                CigarOp::Equal
+            } else {
+                CigarOp::Mismatch
+            }
+        })
+    }
+
+    fn cigar_with<F>(&self, y_len: usize, f: F) -> Cigar
+    where
+        F: Fn(usize, usize) -> CigarOp,
+    {
+        let mut cigar = Cigar::new();
+        cigar.push(self.origin().y, CigarOp::SoftClip);
+        for run in self.runs() {
+            match run {
+                Run::Align { x, y } | Run::Match { x, y } | Run::Mismatch { x, y } => {
+                    for (i, j) in x.zip(y) {
+                        cigar.push(1, f(i, j));
+                    }
+                }
+                Run::Delete { x } => cigar.push(x.len(), CigarOp::Deletion),
+                Run::Insert { y } => cigar.push(y.len(), CigarOp::Insertion),
+            }
+        }
+        cigar.push(y_len.saturating_sub(self.end().y), CigarOp::SoftClip);
+        cigar
+    }
+
+    /// Creates an alignment from a CIGAR string, starting at `origin`.
+    ///
+    /// As in SAM, `origin.y` is the start of the query including its
+    /// leading soft clip, which advances the alignment's origin along `y`.
+    ///
+    /// If all aligned operations are `=` or `X` the alignment is classified,
+    /// otherwise they are treated like `M`. `N` is treated like `D` and `P` is ignored.
+    pub fn from_cigar(cigar: &Cigar, origin: Cursor, score: N) -> Alignment<N> {
+        let mut origin = origin;
+        let ops = cigar.ops();
+        let start = ops.iter().take_while(|(_, op)| op.is_clip()).count();
+        for &(len, op) in &ops[..start] {
+            if op == CigarOp::SoftClip {
+                origin.y += len;
+            }
+        }
+        // Clips only occur at either end, as ensured by `Cigar::push()` and parsing:
+        let aligned = ops[start..].iter().take_while(|(_, op)| !op.is_clip());
+        let classified = !aligned.clone().any(|&(_, op)| op == CigarOp::Match);
+        let mut cursor = origin;
+        let mut runs = vec![];
+        for &(len, op) in aligned {
+            let x = cursor.x..(cursor.x + len);
+            let y = cursor.y..(cursor.y + len);
+            let run = match op {
+                CigarOp::Equal if classified => Run::Match { x, y },
+                CigarOp::Mismatch if classified => Run::Mismatch { x, y },
+                CigarOp::Match | CigarOp::Equal | CigarOp::Mismatch => Run::Align { x, y },
+                CigarOp::Deletion | CigarOp::Skip => Run::Delete { x },
+                CigarOp::Insertion => Run::Insert { y },
+                CigarOp::Padding | CigarOp::SoftClip | CigarOp::HardClip => continue,
+            };
+            cursor = match &run {
+                Run::Delete { x } => Cursor { x: x.end, ..cursor },
+                Run::Insert { y } => Cursor { y: y.end, ..cursor },
+                _ => Cursor {
+                    x: cursor.x + len,
+                    y: cursor.y + len,
+                },
+            };
+            runs.push(run);
+        }
+        Alignment::from_runs(runs, origin, score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{AlignmentSet, InMemoryAlignmentMatrix, SmithWaterman, Step};
+
+    #[test]
+    fn cigar_works() {
+        let x = b"TTTTGATTACAGGGG";
+        let y = b"CCGATACACC";
+        let strategy = SmithWaterman::new(2, -1, -1, -1);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy, |i, j| x[i] == y[j]).unwrap();
+        let alignment = set.local_alignment();
+
+        let cigar = alignment.cigar(y.len());
+        assert_eq!(cigar.to_string(), "2S3M1D3M2S");
+        let extended = alignment.extended_cigar(x, y);
+        assert_eq!(extended.to_string(), "2S3=1D3=2S");
+        // A too short query doesn't underflow:
+        assert_eq!(alignment.cigar(0).to_string(), "2S3M1D3M");
+
+        let origin = Cursor {
+            x: alignment.origin().x,
+            y: 0,
+        };
+        let parsed: Cigar = extended.to_string().parse().unwrap();
+        let restored = Alignment::from_cigar(&parsed, origin, alignment.score());
+        assert_eq!(restored.origin(), alignment.origin());
+        assert!(restored.is_classified());
+        let steps: Vec<Step> = restored.steps().collect();
+        let expected: Vec<Step> = alignment.classify(|i, j| x[i] == y[j]).steps().collect();
+        assert_eq!(steps, expected);
+    }
+
+    #[test]
+    fn classification_round_trips() {
+        let cigar: Cigar = "2S2=1X2N1P1I1=".parse().unwrap();
+        let origin = Cursor { x: 1, y: 0 };
+        let alignment = Alignment::from_cigar(&cigar, origin, 0);
+        assert_eq!(alignment.origin(), &Cursor { x: 1, y: 2 });
+        assert_eq!(alignment.end(), &Cursor { x: 7, y: 7 });
+        let steps: Vec<Step> = alignment.steps().collect();
+        assert_eq!(steps[2], Step::Mismatch { x: 3, y: 4 });
+        assert_eq!(steps[5], Step::Insert { y: 5 });
+        assert_eq!(steps[6], Step::Match { x: 6, y: 6 });
+
+        let (x, y) = (b"TACGTTAT", b"GGACTTA");
+        assert_eq!(alignment.extended_cigar(x, y).to_string(), "2S2=1X2D1I1=");
+    }
+
+    #[test]
+    fn mixed_cigars_arent_classified() {
+        let cigar: Cigar = "3M2=1X".parse().unwrap();
+        let alignment = Alignment::from_cigar(&cigar, Cursor { x: 0, y: 0 }, 0);
+        assert!(!alignment.is_classified());
+        let steps: Vec<Step> = alignment.steps().collect();
+        assert_eq!(steps.len(), 6);
+        assert!(steps.iter().all(|step| matches!(step, Step::Align { .. })));
+    }
+
+    #[test]
+    fn push_works() {
+        let mut cigar = Cigar::new();
+        cigar.push(2, CigarOp::HardClip);
+        cigar.push(1, CigarOp::SoftClip);
+        cigar.push(3, CigarOp::Match);
+        cigar.push(2, CigarOp::Match);
+        cigar.push(1, CigarOp::SoftClip);
+        cigar.push(4, CigarOp::HardClip);
+        assert_eq!(cigar.to_string(), "2H1S5M1S4H");
+
+        let misplaced = Err(ParseCigarError::MisplacedClip);
+        assert_eq!(cigar.clone().try_push(1, CigarOp::Match), misplaced);
+        assert_eq!(cigar.clone().try_push(1, CigarOp::SoftClip), misplaced);
+        let mut leading = Cigar::new();
+        leading.push(1, CigarOp::SoftClip);
+        assert_eq!(leading.clone().try_push(1, CigarOp::HardClip), misplaced);
+        leading.push(1, CigarOp::Insertion);
+        leading.push(1, CigarOp::SoftClip);
+        assert_eq!(leading.try_push(1, CigarOp::Deletion), misplaced);
+    }
+
+    #[test]
+    fn parsing_works() {
+        let cigar: Cigar = "5H3S10M2I1D4=1X7S".parse().unwrap();
+        assert_eq!(cigar.ops().len(), 8);
+        assert_eq!(cigar.to_string(), "5H3S10M2I1D4=1X7S");
+        let cigar: Cigar = "3M100N2M1P1I".parse().unwrap();
+        assert_eq!(cigar.ops()[1], (100, CigarOp::Skip));
+        assert_eq!(cigar.ops()[3], (1, CigarOp::Padding));
+        assert!("*".parse::<Cigar>().unwrap().is_empty());
+
+        assert_eq!("M".parse::<Cigar>(), Err(ParseCigarError::MissingLength));
+        assert_eq!("0M".parse::<Cigar>(), Err(ParseCigarError::InvalidLength));
+        assert_eq!(
+            "10M5".parse::<Cigar>(),
+            Err(ParseCigarError::MissingOperation)
+        );
+        assert_eq!(
+            "10Q".parse::<Cigar>(),
+            Err(ParseCigarError::InvalidOperation('Q'))
+        );
+        assert_eq!(
+            "5M3S5M".parse::<Cigar>(),
+            Err(ParseCigarError::MisplacedClip)
+        );
+        assert_eq!(
+            "3S5H5M".parse::<Cigar>(),
+            Err(ParseCigarError::MisplacedClip)
+        );
+    }
+}
@@ -1,6 +1,8 @@
+use crate::error::Error;
 use crate::pair::step_mask::StepMask;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cursor {
     pub x: usize,
     pub y: usize,
@@ -15,22 +17,46 @@ impl Cursor {
         self.apply_step(step_mask, false)
     }
 
+    /// Like `apply_forwards_step`, but failing on invalid step masks instead of panicking.
+    pub fn try_apply_forwards_step(&mut self, step_mask: StepMask) -> Result<(), Error> {
+        self.try_apply_step(step_mask, true)
+    }
+
+    /// Like `apply_backwards_step`, but failing on invalid step masks
+    /// or steps leading past the origin instead of panicking.
+    pub fn try_apply_backwards_step(&mut self, step_mask: StepMask) -> Result<(), Error> {
+        self.try_apply_step(step_mask, false)
+    }
+
     fn apply_step(&mut self, step_mask: StepMask, forward: bool) {
-        let delta = match step_mask {
+        if self.try_apply_step(step_mask, forward).is_err() {
+            panic!("Invalid step_mask.");
+        }
+    }
+
+    fn try_apply_step(&mut self, step_mask: StepMask, forward: bool) -> Result<(), Error> {
+        let error = Error::InvalidStep {
+            cursor: *self,
+            step_mask,
+        };
+        let delta = match step_mask.direction() {
             StepMask::ALIGN => (1, 1),
             StepMask::INSERT => (0, 1),
             StepMask::DELETE => (1, 0),
             StepMask::STOP => (0, 0),
-            _ => {
-                panic!("Invalid step_mask.");
-            }
+            _ => return Err(error),
         };
-        if forward {
-            self.x += delta.0;
-            self.y += delta.1;
+        let (x, y) = if forward {
+            (self.x.checked_add(delta.0), self.y.checked_add(delta.1))
         } else {
-            self.x -= delta.0;
-            self.y -= delta.1;
+            (self.x.checked_sub(delta.0), self.y.checked_sub(delta.1))
+        };
+        match (x, y) {
+            (Some(x), Some(y)) => {
+                *self = Cursor { x, y };
+                Ok(())
+            }
+            _ => Err(error),
         }
     }
 }
@@ -62,4 +88,16 @@ mod tests {
             assert_eq!(cursor, Cursor { x: 9, y: 10 });
         }
     }
+
+    #[test]
+    fn try_apply_works() {
+        let mut cursor = Cursor { x: 0, y: 10 };
+        assert!(cursor.try_apply_backwards_step(StepMask::INSERT).is_ok());
+        assert_eq!(cursor, Cursor { x: 0, y: 9 });
+        assert!(cursor.try_apply_backwards_step(StepMask::ALIGN).is_err());
+        assert!(cursor
+            .try_apply_forwards_step(StepMask::ALIGN | StepMask::INSERT)
+            .is_err());
+        assert_eq!(cursor, Cursor { x: 0, y: 9 });
+    }
 }
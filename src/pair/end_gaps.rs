@@ -0,0 +1,12 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// End gaps that are free of cost, i.e. leading or trailing
+    /// symbols of either sequence that may be left unaligned.
+    pub struct EndGaps: u8 {
+        const X_LEADING  = 0b00000001;
+        const X_TRAILING = 0b00000010;
+        const Y_LEADING  = 0b00000100;
+        const Y_TRAILING = 0b00001000;
+    }
+}
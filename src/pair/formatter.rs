@@ -0,0 +1,250 @@
+use std::fmt::{Display, Write};
+
+use crate::pair::alignment::Alignment;
+use crate::pair::score::Score;
+use crate::pair::step::Step;
+
+// A single column of the pairwise layout.
+struct Column {
+    x: Option<String>,
+    y: Option<String>,
+    mid: char,
+}
+
+/// Renders alignments as wrapped, coordinate-annotated blocks of pairwise layout,
+/// similar to the output of BLAST or EMBOSS:
+///
+/// ```text
+/// x 1 GATTACA 7
+///     ||| |||
+/// y 1 GAT-ACA 6
+/// ```
+#[derive(Clone, Debug)]
+pub struct AlignmentFormatter {
+    width: usize,
+    gap: char,
+    show_score: bool,
+}
+
+impl Default for AlignmentFormatter {
+    fn default() -> AlignmentFormatter {
+        AlignmentFormatter {
+            width: 60,
+            gap: '-',
+            show_score: true,
+        }
+    }
+}
+
+impl AlignmentFormatter {
+    pub fn new() -> AlignmentFormatter {
+        AlignmentFormatter::default()
+    }
+
+    /// Sets the number of columns per block (default: `60`).
+    pub fn width(mut self, width: usize) -> AlignmentFormatter {
+        assert!(width > 0, "Width must be positive.");
+        self.width = width;
+        self
+    }
+
+    /// Sets the character used for gaps (default: `'-'`).
+    pub fn gap(mut self, gap: char) -> AlignmentFormatter {
+        self.gap = gap;
+        self
+    }
+
+    /// Sets whether to print the alignment's score above the blocks (default: `true`).
+    pub fn show_score(mut self, show_score: bool) -> AlignmentFormatter {
+        self.show_score = show_score;
+        self
+    }
+
+    /// Formats `alignment` over sequences `x` and `y`,
+    /// marking identical symbols with `|`.
+    pub fn format<N, T>(&self, alignment: &Alignment<N>, x: &[T], y: &[T]) -> String
+    where
+        N: Score + Display,
+        T: Copy + Into<char> + PartialEq,
+    {
+        self.format_with(alignment, x, y, |_, _| -1)
+    }
+
+    /// Formats `alignment` over sequences `x` and `y`,
+    /// marking identical symbols with `|` and others based on their `similarity`:
+    /// `:` if positive, `.` if zero and a space if negative.
+    pub fn format_with<N, T, F>(
+        &self,
+        alignment: &Alignment<N>,
+        x: &[T],
+        y: &[T],
+        similarity: F,
+    ) -> String
+    where
+        N: Score + Display,
+        T: Copy + Into<char> + PartialEq,
+        F: Fn(T, T) -> isize,
+    {
+        let mid = |a: &T, b: &T| {
+            if a == b {
+                return '|';
+            }
+            match similarity(*a, *b) {
+                s if s > 0 => ':',
+                0 => '.',
+                _ => ' ',
+            }
+        };
+        self.render(alignment, x, y, mid, |&symbol| symbol.into().to_string())
+    }
+
+    /// Formats `alignment` over sequences `x` and `y` of strings (e.g. graphemes),
+    /// with each string taking up a single column, marking identical ones with `|`.
+    pub fn format_strs<N, T>(&self, alignment: &Alignment<N>, x: &[T], y: &[T]) -> String
+    where
+        N: Score + Display,
+        T: AsRef<str> + PartialEq,
+    {
+        let mid = |a: &T, b: &T| if a == b { '|' } else { ' ' };
+        self.render(alignment, x, y, mid, |symbol| symbol.as_ref().to_string())
+    }
+
+    // Renders the alignment, using `mid` for the middle line's
+    // character of aligned symbols and `symbol` for printing them.
+    fn render<N, T, F, G>(
+        &self,
+        alignment: &Alignment<N>,
+        x: &[T],
+        y: &[T],
+        mid: F,
+        symbol: G,
+    ) -> String
+    where
+        N: Score + Display,
+        F: Fn(&T, &T) -> char,
+        G: Fn(&T) -> String,
+    {
+        let mut output = String::new();
+        if self.show_score {
+            let _ = writeln!(output, "Score: {}", alignment.score());
+            let _ = writeln!(output);
+        }
+
+        let columns: Vec<Column> = alignment
+            .steps()
+            .map(|step| match step {
+                Step::Align { x: i, y: j }
+                | Step::Match { x: i, y: j }
+                | Step::Mismatch { x: i, y: j } => Column {
+                    x: Some(symbol(&x[i])),
+                    y: Some(symbol(&y[j])),
+                    mid: mid(&x[i], &y[j]),
+                },
+                Step::Delete { x: i } => Column {
+                    x: Some(symbol(&x[i])),
+                    y: None,
+                    mid: ' ',
+                },
+                Step::Insert { y: j } => Column {
+                    x: None,
+                    y: Some(symbol(&y[j])),
+                    mid: ' ',
+                },
+            })
+            .collect();
+
+        let origin = alignment.origin();
+        let (mut x_pos, mut y_pos) = (origin.x, origin.y);
+        let x_end = x_pos + columns.iter().filter(|c| c.x.is_some()).count();
+        let y_end = y_pos + columns.iter().filter(|c| c.y.is_some()).count();
+        let digits = x_end.max(y_end).to_string().len();
+
+        for (index, block) in columns.chunks(self.width).enumerate() {
+            if index > 0 {
+                let _ = writeln!(output);
+            }
+            let gap = self.gap.to_string();
+            let x_line: String = block
+                .iter()
+                .map(|c| c.x.as_ref().unwrap_or(&gap).as_str())
+                .collect();
+            let y_line: String = block
+                .iter()
+                .map(|c| c.y.as_ref().unwrap_or(&gap).as_str())
+                .collect();
+            let mid_line: String = block.iter().map(|c| c.mid).collect();
+            let x_len = block.iter().filter(|c| c.x.is_some()).count();
+            let y_len = block.iter().filter(|c| c.y.is_some()).count();
+
+            let _ = writeln!(
+                output,
+                "x {:>w$} {} {}",
+                x_pos + 1,
+                x_line,
+                x_pos + x_len,
+                w = digits
+            );
+            let _ = writeln!(output, "  {:w$} {}", "", mid_line.trim_end(), w = digits);
+            let _ = writeln!(
+                output,
+                "y {:>w$} {} {}",
+                y_pos + 1,
+                y_line,
+                y_pos + y_len,
+                w = digits
+            );
+            x_pos += x_len;
+            y_pos += y_len;
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::cursor::Cursor;
+    use crate::pair::step_mask::StepMask;
+
+    #[test]
+    fn format_works() {
+        let x: Vec<char> = "AGATTACA".chars().collect();
+        let y: Vec<char> = "GAKACAA".chars().collect();
+        let steps = vec![
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::DELETE,
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::INSERT,
+        ];
+        let alignment = Alignment::new(Cursor { x: 1, y: 0 }, steps, 3);
+
+        let formatter = AlignmentFormatter::new().width(5);
+        let similarity = |a: char, b: char| if (a, b) == ('T', 'K') { 1 } else { -1 };
+        let output = formatter.format_with(&alignment, &x, &y, similarity);
+        let expected = "\
+Score: 3
+
+x 2 GATTA 6
+    ||: |
+y 1 GAK-A 4
+
+x 7 CA- 8
+    ||
+y 5 CAA 7
+";
+        assert_eq!(output, expected);
+
+        let formatter = formatter.width(10).gap('.').show_score(false);
+        let output = formatter.format(&alignment, &x, &y);
+        let expected = "\
+x 2 GATTACA. 8
+    ||  |||
+y 1 GAK.ACAA 7
+";
+        assert_eq!(output, expected);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::error::Error;
+use crate::pair::alignment::Alignment;
+use crate::pair::cursor::Cursor;
+use crate::pair::score::{self, Score};
+use crate::pair::step_mask::StepMask;
+use crate::pair::strategy::Strategy;
+use crate::pair::substitution::Substitution;
+
+// Blocks with at most this many cells are solved with a full traceback matrix.
+const BLOCK_SIZE: usize = 4096;
+
+/// Linear-space global alignment using Hirschberg's divide-and-conquer approach.
+///
+/// Returns the same alignment as `AlignmentSet::global_alignment()`,
+/// while only keeping `O(x_len + y_len)` scores, instead of a full traceback matrix.
+///
+/// Only global strategies with linear gap scores and without free end gaps are supported.
+#[derive(Clone, Debug)]
+pub struct Hirschberg<S> {
+    strategy: S,
+}
+
+impl<S> Hirschberg<S>
+where
+    S: Strategy,
+{
+    /// Panics if `strategy` isn't supported, see `try_new()`.
+    pub fn new(strategy: S) -> Hirschberg<S> {
+        match Self::try_new(strategy) {
+            Ok(hirschberg) => hirschberg,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Fails with `Error::UnsupportedStrategy` for local strategies,
+    /// affine gap scores or free end gaps.
+    pub fn try_new(strategy: S) -> Result<Hirschberg<S>, Error> {
+        if strategy.is_local() {
+            return Err(Error::UnsupportedStrategy(
+                "Hirschberg requires a global strategy",
+            ));
+        }
+        if strategy.has_affine_gaps() {
+            return Err(Error::UnsupportedStrategy(
+                "Hirschberg does not support affine gaps",
+            ));
+        }
+        if !strategy.free_end_gaps().is_empty() {
+            return Err(Error::UnsupportedStrategy(
+                "Hirschberg does not support free end gaps",
+            ));
+        }
+        Ok(Hirschberg { strategy })
+    }
+
+    pub fn global_alignment<F, R>(&self, x_len: usize, y_len: usize, f: F) -> Alignment<S::Score>
+    where
+        F: Fn(usize, usize) -> R,
+        R: Substitution<S::Score>,
+    {
+        let strategy = &self.strategy;
+        let top: Vec<S::Score> = (0..=x_len)
+            .map(|x| strategy.total_score(strategy.delete_score().times(x)))
+            .collect();
+        let left: Vec<S::Score> = (0..=y_len)
+            .map(|y| strategy.total_score(strategy.insert_score().times(y)))
+            .collect();
+        let mut steps = Vec::with_capacity(x_len + y_len);
+        let origin = Cursor { x: 0, y: 0 };
+        let score = self.align(&f, origin, top, left, &mut steps);
+        Alignment::new(origin, steps, score)
+    }
+
+    // Appends the steps of the path leading from `origin` to the block's
+    // bottom-right corner, given the scores of its top row and left column,
+    // returning the corner's score.
+    //
+    // Takes ownership of the scores, so that they can be dropped before recursing,
+    // with only the lower half's (disjoint) scores being kept while recursing into
+    // the upper half, keeping the overall memory linear.
+    fn align<F, R>(
+        &self,
+        f: &F,
+        origin: Cursor,
+        top: Vec<S::Score>,
+        left: Vec<S::Score>,
+        steps: &mut Vec<StepMask>,
+    ) -> S::Score
+    where
+        F: Fn(usize, usize) -> R,
+        R: Substitution<S::Score>,
+    {
+        let width = top.len() - 1;
+        let height = left.len() - 1;
+
+        if (height < 2) || ((width + 1) * (height + 1) <= BLOCK_SIZE) {
+            return self.align_block(f, origin, &top, &left, steps);
+        }
+
+        let middle = height / 2;
+
+        // Forward pass, keeping track of the column at which the traceback
+        // from each cell below the middle row would first enter the middle row:
+        let mut row = top.clone();
+        let mut middle_row = vec![];
+        let mut crossings: Vec<usize> = (0..=width).collect();
+        for (y, &left_score) in left.iter().enumerate().skip(1) {
+            let mut diagonal = row[0];
+            let mut diagonal_crossing = crossings[0];
+            row[0] = left_score;
+            for x in 1..=width {
+                let cursor = Cursor { x, y };
+                let (steps, score) = self.cell(f, origin, cursor, (diagonal, row[x], row[x - 1]));
+                if y > middle {
+                    let crossing = match Self::preferred_step(steps) {
+                        StepMask::DELETE => crossings[x - 1],
+                        StepMask::INSERT => crossings[x],
+                        _ => diagonal_crossing,
+                    };
+                    diagonal_crossing = crossings[x];
+                    crossings[x] = crossing;
+                }
+                diagonal = row[x];
+                row[x] = score;
+            }
+            if y == middle {
+                middle_row = row.clone();
+            }
+        }
+        let score = row[width];
+        let crossing = crossings[width];
+        drop(crossings);
+
+        // Second pass, recovering the scores of the crossing column below the middle row:
+        row.truncate(crossing + 1);
+        row.copy_from_slice(&middle_row[..=crossing]);
+        let mut column = vec![row[crossing]];
+        for (y, &left_score) in left.iter().enumerate().skip(middle + 1) {
+            let mut diagonal = row[0];
+            row[0] = left_score;
+            for x in 1..=crossing {
+                let cursor = Cursor { x, y };
+                let (_, score) = self.cell(f, origin, cursor, (diagonal, row[x], row[x - 1]));
+                diagonal = row[x];
+                row[x] = score;
+            }
+            column.push(row[crossing]);
+        }
+
+        drop(row);
+        let lower_top = middle_row.split_off(crossing);
+        drop(middle_row);
+        let (mut upper_top, mut upper_left) = (top, left);
+        upper_top.truncate(crossing + 1);
+        upper_top.shrink_to_fit();
+        upper_left.truncate(middle + 1);
+        upper_left.shrink_to_fit();
+        self.align(f, origin, upper_top, upper_left, steps);
+
+        let lower_origin = Cursor {
+            x: origin.x + crossing,
+            y: origin.y + middle,
+        };
+        self.align(f, lower_origin, lower_top, column, steps);
+
+        score
+    }
+
+    fn align_block<F, R>(
+        &self,
+        f: &F,
+        origin: Cursor,
+        top: &[S::Score],
+        left: &[S::Score],
+        steps: &mut Vec<StepMask>,
+    ) -> S::Score
+    where
+        F: Fn(usize, usize) -> R,
+        R: Substitution<S::Score>,
+    {
+        let width = top.len() - 1;
+        let height = left.len() - 1;
+
+        let mut matrix = vec![StepMask::DELETE; (width + 1) * (height + 1)];
+        let mut row = top.to_vec();
+        for y in 1..=height {
+            let mut diagonal = row[0];
+            row[0] = left[y];
+            matrix[y * (width + 1)] = StepMask::INSERT;
+            for x in 1..=width {
+                let cursor = Cursor { x, y };
+                let (steps, score) = self.cell(f, origin, cursor, (diagonal, row[x], row[x - 1]));
+                matrix[x + (y * (width + 1))] = steps;
+                diagonal = row[x];
+                row[x] = score;
+            }
+        }
+
+        let mut block_steps = vec![];
+        let mut cursor = Cursor {
+            x: width,
+            y: height,
+        };
+        while (cursor.x > 0) || (cursor.y > 0) {
+            let step = Self::preferred_step(matrix[cursor.x + (cursor.y * (width + 1))]);
+            assert!(
+                step != StepMask::STOP,
+                "Hirschberg requires a global strategy."
+            );
+            cursor.apply_backwards_step(step);
+            block_steps.push(step);
+        }
+        steps.extend(block_steps.into_iter().rev());
+
+        row[width]
+    }
+
+    fn cell<F, R>(
+        &self,
+        f: &F,
+        origin: Cursor,
+        cursor: Cursor,
+        previous_scores: (S::Score, S::Score, S::Score),
+    ) -> (StepMask, S::Score)
+    where
+        F: Fn(usize, usize) -> R,
+        R: Substitution<S::Score>,
+    {
+        let strategy = &self.strategy;
+        let (diagonal, up, left) = previous_scores;
+        let substitution = f(origin.x + cursor.x - 1, origin.y + cursor.y - 1).score(strategy);
+        let align = diagonal.saturating_add(substitution);
+        let delete = left.saturating_add(strategy.delete_score());
+        let insert = up.saturating_add(strategy.insert_score());
+        let steps = strategy.step_mask(align, insert, delete);
+        let score = strategy.total_score(score::max(score::max(align, delete), insert));
+        (steps, score)
+    }
+
+    // The step taken first when tracing back, matching the order of `Alignments`.
+    fn preferred_step(steps: StepMask) -> StepMask {
+        [StepMask::DELETE, StepMask::INSERT, StepMask::ALIGN]
+            .iter()
+            .copied()
+            .find(|&mask| steps.contains(mask))
+            .unwrap_or(StepMask::STOP)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::test_utils::sequence;
+    use crate::pair::{AlignmentSet, InMemoryAlignmentMatrix, NeedlemanWunsch, Step};
+
+    fn assert_matches_alignment_set(x: &[u8], y: &[u8]) {
+        let strategy = NeedlemanWunsch::new(2, -1, -2, -1);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(x.len(), y.len(), strategy.clone(), |i, j| x[i] == y[j]).unwrap();
+        let expected = set.global_alignment();
+
+        let hirschberg = Hirschberg::new(strategy);
+        let alignment = hirschberg.global_alignment(x.len(), y.len(), |i, j| x[i] == y[j]);
+
+        assert_eq!(alignment.score(), set.global_score());
+        assert_eq!(alignment.origin(), expected.origin());
+        let steps: Vec<Step> = alignment.steps().collect();
+        let expected_steps: Vec<Step> = expected.steps().collect();
+        assert_eq!(steps, expected_steps);
+    }
+
+    #[test]
+    fn small_works() {
+        assert_matches_alignment_set(b"GATTACA", b"GCATGCA");
+        assert_matches_alignment_set(b"", b"GCATGCA");
+        assert_matches_alignment_set(b"GATTACA", b"");
+    }
+
+    #[test]
+    fn unsupported_strategies_fail() {
+        use crate::pair::{EndGaps, SemiGlobal, SmithWaterman};
+
+        let local = Hirschberg::try_new(SmithWaterman::new(2, -1, -1, -1));
+        assert!(matches!(local, Err(Error::UnsupportedStrategy(_))));
+        let affine = Hirschberg::try_new(NeedlemanWunsch::affine(1, -1, -3, -1));
+        assert!(matches!(affine, Err(Error::UnsupportedStrategy(_))));
+        let strategy = SemiGlobal::new(NeedlemanWunsch::new(1, -1, -1, -1), EndGaps::all());
+        let semi_global = Hirschberg::try_new(strategy);
+        assert!(matches!(semi_global, Err(Error::UnsupportedStrategy(_))));
+    }
+
+    #[test]
+    fn large_works() {
+        for seed in 0..8 {
+            let x = sequence(150 + (seed as usize) * 10, seed, b"ACGT");
+            let y = sequence(170 - (seed as usize) * 15, seed + 100, b"ACGT");
+            assert_matches_alignment_set(&x, &y);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::ops::Range;
+
+use crate::pair::alignment::Alignment;
+use crate::pair::run::Run;
+use crate::pair::score::Score;
+
+/// How to map positions that aren't aligned to any position of the other sequence,
+/// i.e. that fall into gaps or outside of the alignment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GapPolicy {
+    /// Map to the nearest preceding aligned position.
+    Previous,
+    /// Map to the nearest following aligned position.
+    Next,
+    /// Don't map at all.
+    None,
+}
+
+// A run of aligned positions.
+#[derive(Copy, Clone, Debug)]
+struct Block {
+    x: usize,
+    y: usize,
+    len: usize,
+}
+
+/// An index mapping positions between the two sequences of an alignment,
+/// answering queries in `O(log n)` for `n` runs of aligned positions.
+#[derive(Clone, Debug)]
+pub struct Liftover {
+    blocks: Vec<Block>,
+}
+
+impl Liftover {
+    pub fn new<N: Score>(alignment: &Alignment<N>) -> Liftover {
+        let blocks = alignment
+            .runs()
+            .filter_map(|run| match run {
+                Run::Align { x, y } | Run::Match { x, y } | Run::Mismatch { x, y } => Some(Block {
+                    x: x.start,
+                    y: y.start,
+                    len: x.len(),
+                }),
+                _ => None,
+            })
+            .collect();
+        Liftover { blocks }
+    }
+
+    /// Maps position `x` to its aligned position in `y`.
+    pub fn x_to_y(&self, x: usize, policy: GapPolicy) -> Option<usize> {
+        self.map(x, policy, |block| (block.x, block.y))
+    }
+
+    /// Maps position `y` to its aligned position in `x`.
+    pub fn y_to_x(&self, y: usize, policy: GapPolicy) -> Option<usize> {
+        self.map(y, policy, |block| (block.y, block.x))
+    }
+
+    /// Maps a range of `x` to the smallest range of `y` covering all of its aligned positions,
+    /// or `None` if none of its positions are aligned.
+    pub fn x_range_to_y(&self, range: Range<usize>) -> Option<Range<usize>> {
+        self.map_range(range, |block| (block.x, block.y))
+    }
+
+    /// Maps a range of `y` to the smallest range of `x` covering all of its aligned positions,
+    /// or `None` if none of its positions are aligned.
+    pub fn y_range_to_x(&self, range: Range<usize>) -> Option<Range<usize>> {
+        self.map_range(range, |block| (block.y, block.x))
+    }
+
+    // Maps `position` using `starts`, which returns a block's
+    // start positions in the source and the target sequence.
+    fn map<F>(&self, position: usize, policy: GapPolicy, starts: F) -> Option<usize>
+    where
+        F: Fn(&Block) -> (usize, usize),
+    {
+        // The number of blocks starting at or before `position`:
+        let index = self
+            .blocks
+            .partition_point(|block| starts(block).0 <= position);
+        if index > 0 {
+            let block = &self.blocks[index - 1];
+            let (source, target) = starts(block);
+            if position < source + block.len {
+                return Some(target + (position - source));
+            }
+            if policy == GapPolicy::Previous {
+                return Some(target + block.len - 1);
+            }
+        }
+        match policy {
+            GapPolicy::Next => self.blocks.get(index).map(|block| starts(block).1),
+            _ => None,
+        }
+    }
+
+    fn map_range<F>(&self, range: Range<usize>, starts: F) -> Option<Range<usize>>
+    where
+        F: Fn(&Block) -> (usize, usize) + Copy,
+    {
+        if range.is_empty() {
+            return None;
+        }
+        let start = self.map(range.start, GapPolicy::Next, starts)?;
+        let last = self.map(range.end - 1, GapPolicy::Previous, starts)?;
+        if start > last {
+            return None;
+        }
+        Some(start..(last + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::cigar::Cigar;
+    use crate::pair::cursor::Cursor;
+
+    fn liftover() -> Liftover {
+        // x: 2..17, y: 0..11
+        let cigar: Cigar = "3M2D4M1I2M3D1M".parse().unwrap();
+        let alignment = Alignment::from_cigar(&cigar, Cursor { x: 2, y: 0 }, 0);
+        Liftover::new(&alignment)
+    }
+
+    #[test]
+    fn positions_work() {
+        let liftover = liftover();
+        assert_eq!(liftover.x_to_y(3, GapPolicy::None), Some(1));
+        assert_eq!(liftover.x_to_y(8, GapPolicy::None), Some(4));
+        assert_eq!(liftover.x_to_y(11, GapPolicy::None), Some(8));
+
+        assert_eq!(liftover.x_to_y(5, GapPolicy::None), None);
+        assert_eq!(liftover.x_to_y(5, GapPolicy::Previous), Some(2));
+        assert_eq!(liftover.x_to_y(5, GapPolicy::Next), Some(3));
+
+        assert_eq!(liftover.x_to_y(0, GapPolicy::Previous), None);
+        assert_eq!(liftover.x_to_y(0, GapPolicy::Next), Some(0));
+        assert_eq!(liftover.x_to_y(20, GapPolicy::Previous), Some(10));
+        assert_eq!(liftover.x_to_y(20, GapPolicy::Next), None);
+
+        assert_eq!(liftover.y_to_x(4, GapPolicy::None), Some(8));
+        assert_eq!(liftover.y_to_x(7, GapPolicy::None), None);
+        assert_eq!(liftover.y_to_x(7, GapPolicy::Previous), Some(10));
+        assert_eq!(liftover.y_to_x(7, GapPolicy::Next), Some(11));
+    }
+
+    #[test]
+    fn ranges_work() {
+        let liftover = liftover();
+        assert_eq!(liftover.x_range_to_y(3..10), Some(1..6));
+        assert_eq!(liftover.x_range_to_y(5..7), None);
+        assert_eq!(liftover.x_range_to_y(0..3), Some(0..1));
+        assert_eq!(liftover.y_range_to_x(6..9), Some(10..12));
+    }
+}
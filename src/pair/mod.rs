@@ -1,36 +1,90 @@
+pub mod band;
 pub mod cursor;
+pub mod end_gaps;
 pub mod run;
+pub mod score;
 pub mod step;
 pub mod step_mask;
 pub mod strategy;
+pub mod substitution;
+pub mod substitution_matrix;
 
 pub mod needleman_wunsch;
+pub mod semi_global;
 pub mod smith_waterman;
 
 pub mod alignment;
+pub mod cigar;
+pub mod formatter;
+pub mod liftover;
+pub mod paf;
+pub mod rescore;
 pub mod runs;
+pub mod sam;
+pub mod statistics;
 pub mod steps;
 
+pub mod aligner;
 pub mod alignment_matrix;
+pub mod alignment_scores;
 pub mod alignment_set;
 pub mod alignments;
+#[cfg(feature = "parallel")]
+pub mod batch;
+pub mod hirschberg;
+pub mod striped;
 
+#[cfg(test)]
+mod test_utils;
+
+pub use self::band::Band;
 pub use self::cursor::Cursor;
+pub use self::end_gaps::EndGaps;
 pub use self::run::Run;
+pub use self::score::{PersistableScore, Score};
 pub use self::step::Step;
 pub use self::step_mask::StepMask;
 pub use self::strategy::Strategy;
+pub use self::substitution::Substitution;
+pub use self::substitution_matrix::{
+    SubstitutionMatrix, BLOSUM45, BLOSUM62, BLOSUM80, PAM250, PAM30, PAM70,
+};
 
 pub use self::alignment_matrix::{
+    banded::AlignmentMatrix as BandedAlignmentMatrix,
     in_memory::AlignmentMatrix as InMemoryAlignmentMatrix,
     memory_mapped::AlignmentMatrix as MemoryMappedAlignmentMatrix, AlignmentMatrix,
 };
+
+/// An in-memory alignment matrix storing each cell in `BITS` bits.
+pub type PackedAlignmentMatrix<const BITS: usize = 3> =
+    self::alignment_matrix::packed::AlignmentMatrix<Vec<u8>, BITS>;
+/// A memory-mapped alignment matrix storing each cell in `BITS` bits.
+pub type PackedMemoryMappedAlignmentMatrix<const BITS: usize = 3> =
+    self::alignment_matrix::packed::AlignmentMatrix<
+        self::alignment_matrix::packed::MmapBuffer,
+        BITS,
+    >;
 pub use self::needleman_wunsch::NeedlemanWunsch;
+pub use self::semi_global::SemiGlobal;
 pub use self::smith_waterman::SmithWaterman;
 
-pub use self::alignment::Alignment;
+pub use self::alignment::{Alignment, Summary};
+pub use self::cigar::{Cigar, CigarOp, ParseCigarError};
+pub use self::formatter::AlignmentFormatter;
+pub use self::liftover::{GapPolicy, Liftover};
+pub use self::paf::{PafRecord, PafWriter};
+pub use self::rescore::{Rescore, ScoredStep};
 pub use self::runs::Runs;
+pub use self::sam::{SamRecord, SamWriter};
+pub use self::statistics::Statistics;
 pub use self::steps::Steps;
 
+pub use self::aligner::{Aligner, Backend, Budget, Mode, PairwiseAlignment};
+pub use self::alignment_scores::AlignmentScores;
 pub use self::alignment_set::AlignmentSet;
 pub use self::alignments::Alignments;
+#[cfg(feature = "parallel")]
+pub use self::batch::{Batch, BatchResult, ResultKind, Scope};
+pub use self::hirschberg::Hirschberg;
+pub use self::striped::StripedSmithWaterman;
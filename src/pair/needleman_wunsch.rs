@@ -1,47 +1,73 @@
+use crate::pair::score::Score;
 use crate::pair::step_mask::StepMask;
 use crate::pair::strategy::Strategy;
 
 #[derive(Clone, Debug)]
-pub struct NeedlemanWunsch {
-    pub equal: isize,
-    pub align: isize,
-    pub insert: isize,
-    pub delete: isize,
+pub struct NeedlemanWunsch<N = isize> {
+    pub equal: N,
+    pub align: N,
+    pub insert: N,
+    pub delete: N,
+    insert_open: N,
+    delete_open: N,
 }
 
-impl NeedlemanWunsch {
-    pub fn new(equal: isize, align: isize, insert: isize, delete: isize) -> NeedlemanWunsch {
+impl<N: Score> NeedlemanWunsch<N> {
+    pub fn new(equal: N, align: N, insert: N, delete: N) -> NeedlemanWunsch<N> {
         NeedlemanWunsch {
             equal,
             align,
             insert,
             delete,
+            insert_open: N::ZERO,
+            delete_open: N::ZERO,
+        }
+    }
+
+    pub fn affine(equal: N, align: N, open: N, extend: N) -> NeedlemanWunsch<N> {
+        NeedlemanWunsch {
+            equal,
+            align,
+            insert: extend,
+            delete: extend,
+            insert_open: open,
+            delete_open: open,
         }
     }
 }
 
-impl Strategy for NeedlemanWunsch {
-    fn match_score(&self) -> isize {
+impl<N: Score> Strategy for NeedlemanWunsch<N> {
+    type Score = N;
+
+    fn match_score(&self) -> N {
         self.equal
     }
 
-    fn mismatch_score(&self) -> isize {
+    fn mismatch_score(&self) -> N {
         self.align
     }
 
-    fn insert_score(&self) -> isize {
+    fn insert_score(&self) -> N {
         self.insert
     }
 
-    fn delete_score(&self) -> isize {
+    fn delete_score(&self) -> N {
         self.delete
     }
 
-    fn total_score(&self, score: isize) -> isize {
+    fn insert_open_score(&self) -> N {
+        self.insert_open
+    }
+
+    fn delete_open_score(&self) -> N {
+        self.delete_open
+    }
+
+    fn total_score(&self, score: N) -> N {
         score
     }
 
-    fn step_mask(&self, align: isize, insert: isize, delete: isize) -> StepMask {
-        StepMask::from_scores(align, insert, delete)
+    fn step_mask(&self, align: N, insert: N, delete: N) -> StepMask {
+        StepMask::from_scores(align, delete, insert)
     }
 }
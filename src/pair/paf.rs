@@ -0,0 +1,157 @@
+use std::io::{self, Write};
+
+use crate::pair::alignment::Alignment;
+use crate::pair::cigar::{Cigar, CigarOp};
+use crate::pair::sam::write_score_tag;
+use crate::pair::score::Score;
+use crate::pair::step::Step;
+
+/// A pairwise alignment to be written as a PAF record,
+/// with `x` being the target and `y` the query.
+pub struct PafRecord<'a, N = isize> {
+    pub query_name: &'a str,
+    pub target_name: &'a str,
+    pub target: &'a [u8],
+    pub query: &'a [u8],
+    pub alignment: &'a Alignment<N>,
+    /// The relative strand, `'+'` or `'-'`.
+    pub strand: char,
+    /// The mapping quality, `255` if unavailable.
+    pub mapq: u8,
+}
+
+impl<'a, N: Score> PafRecord<'a, N> {
+    pub fn new(
+        query_name: &'a str,
+        target_name: &'a str,
+        target: &'a [u8],
+        query: &'a [u8],
+        alignment: &'a Alignment<N>,
+    ) -> PafRecord<'a, N> {
+        PafRecord {
+            query_name,
+            target_name,
+            target,
+            query,
+            alignment,
+            strand: '+',
+            mapq: 255,
+        }
+    }
+
+    // Returns the number of residue matches.
+    fn matches(&self) -> usize {
+        let (target, query) = (self.target, self.query);
+        let steps = self.alignment.steps();
+        steps
+            .filter(|step| match *step {
+                Step::Align { x, y } | Step::Match { x, y } | Step::Mismatch { x, y } => {
+                    target[x] == query[y]
+                }
+                _ => false,
+            })
+            .count()
+    }
+}
+
+/// Writes PAF records, as produced by minimap2.
+pub struct PafWriter<W> {
+    inner: W,
+    write_score: bool,
+}
+
+impl<W: Write> PafWriter<W> {
+    pub fn new(inner: W) -> PafWriter<W> {
+        PafWriter {
+            inner,
+            write_score: false,
+        }
+    }
+
+    /// Whether to add an `AS:i` tag with the alignment's score,
+    /// rounding float scores (see `Score::to_i64()`).
+    pub fn write_score(mut self, write_score: bool) -> PafWriter<W> {
+        self.write_score = write_score;
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes a record, with a `cg:Z` tag holding the alignment's CIGAR string.
+    pub fn write_record<N>(&mut self, record: &PafRecord<'_, N>) -> io::Result<()>
+    where
+        N: Score,
+    {
+        let alignment = record.alignment;
+        let (origin, end) = (alignment.origin(), alignment.end());
+        let matches = record.matches();
+
+        // PAF's CIGAR strings only cover the aligned region, without clips:
+        let mut cigar = Cigar::new();
+        for &(len, op) in alignment.cigar(record.query.len()).ops() {
+            if op != CigarOp::SoftClip {
+                cigar.push(len, op);
+            }
+        }
+
+        write!(
+            self.inner,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            record.query_name,
+            record.query.len(),
+            origin.y,
+            end.y,
+            record.strand,
+            record.target_name,
+            record.target.len(),
+            origin.x,
+            end.x,
+            matches,
+            alignment.len(),
+            record.mapq
+        )?;
+        if self.write_score {
+            write_score_tag(&mut self.inner, alignment.score())?;
+        }
+        writeln!(self.inner, "\tcg:Z:{}", cigar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{AlignmentSet, InMemoryAlignmentMatrix, SmithWaterman, StepMask};
+
+    #[test]
+    fn paf_works() {
+        let target = b"TTTTGATTACAGCGG";
+        let query = b"CCGATACAGTGG";
+        let strategy = SmithWaterman::new(2, -1, -1, -1);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(target.len(), query.len(), strategy, |x, y| {
+                target[x] == query[y]
+            })
+            .unwrap();
+        let alignment = set.local_alignment();
+
+        let mut writer = PafWriter::new(Vec::new()).write_score(true);
+        let mut record = PafRecord::new("read1", "chr1", target, query, &alignment);
+        record.mapq = 60;
+        writer.write_record(&record).unwrap();
+
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(
+            output,
+            "read1\t12\t2\t12\t+\tchr1\t15\t4\t15\t9\t11\t60\tAS:i:16\tcg:Z:3M1D7M\n"
+        );
+
+        let alignment = Alignment::new(*alignment.origin(), vec![StepMask::ALIGN; 3], 2.4f32);
+        let record = PafRecord::new("read2", "chr1", target, query, &alignment);
+        let mut writer = PafWriter::new(Vec::new()).write_score(true);
+        writer.write_record(&record).unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        assert!(output.contains("\tAS:i:2\tcg:Z:3M\n"));
+    }
+}
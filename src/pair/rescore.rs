@@ -0,0 +1,193 @@
+use crate::pair::alignment::Alignment;
+use crate::pair::end_gaps::EndGaps;
+use crate::pair::score::Score;
+use crate::pair::step::Step;
+use crate::pair::strategy::Strategy;
+use crate::pair::substitution::Substitution;
+
+/// A single column of a rescored alignment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoredStep<N = isize> {
+    pub step: Step,
+    /// The column's own score: the substitution score for alignments,
+    /// the extension (plus opening) score for gaps and zero for free end gaps.
+    pub score: N,
+    /// The alignment's score up to and including the column.
+    pub total: N,
+}
+
+/// The score of an existing alignment under a strategy, broken down per column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rescore<N = isize> {
+    score: N,
+    steps: Vec<ScoredStep<N>>,
+}
+
+impl<N: Score> Rescore<N> {
+    pub fn score(&self) -> N {
+        self.score
+    }
+
+    pub fn steps(&self) -> &[ScoredStep<N>] {
+        &self.steps
+    }
+}
+
+impl<M: Score> Alignment<M> {
+    /// Scores the alignment's path of sequences of length `x_len` and `y_len`
+    /// under `strategy`, comparing symbols using `f`, regardless of its own score.
+    ///
+    /// The path is scored just like `AlignmentSet` scores its cells,
+    /// including `SmithWaterman`'s clamping at zero and free end gaps.
+    pub fn rescore<S, F, R>(
+        &self,
+        x_len: usize,
+        y_len: usize,
+        strategy: &S,
+        f: F,
+    ) -> Rescore<S::Score>
+    where
+        S: Strategy,
+        F: Fn(usize, usize) -> R,
+        R: Substitution<S::Score>,
+    {
+        let end_gaps = strategy.free_end_gaps();
+        let affine = strategy.has_affine_gaps();
+        let zero = S::Score::ZERO;
+
+        let mut total = strategy.total_score(zero);
+        // The (unclamped) score of an ongoing affine gap:
+        let mut gap: Option<(Step, S::Score)> = None;
+        let mut cursor = *self.origin();
+        let mut steps = Vec::with_capacity(self.len());
+        for step in self.steps() {
+            let (extend, open, is_free) = match step {
+                Step::Align { .. } | Step::Match { .. } | Step::Mismatch { .. } => {
+                    (zero, zero, false)
+                }
+                Step::Delete { .. } => {
+                    let is_free = ((cursor.y == 0) && end_gaps.contains(EndGaps::X_LEADING))
+                        || ((cursor.y == y_len) && end_gaps.contains(EndGaps::X_TRAILING));
+                    (
+                        strategy.delete_score(),
+                        strategy.delete_open_score(),
+                        is_free,
+                    )
+                }
+                Step::Insert { .. } => {
+                    let is_free = ((cursor.x == 0) && end_gaps.contains(EndGaps::Y_LEADING))
+                        || ((cursor.x == x_len) && end_gaps.contains(EndGaps::Y_TRAILING));
+                    (
+                        strategy.insert_score(),
+                        strategy.insert_open_score(),
+                        is_free,
+                    )
+                }
+            };
+            let score = match step {
+                Step::Align { x, y } | Step::Match { x, y } | Step::Mismatch { x, y } => {
+                    gap = None;
+                    let score = f(x, y).score(strategy);
+                    total = strategy.total_score(total.saturating_add(score));
+                    score
+                }
+                _ if is_free => {
+                    gap = None;
+                    zero
+                }
+                _ if affine => {
+                    let (score, gap_score) = match gap {
+                        Some((ref previous, gap_score)) if previous.mask() == step.mask() => {
+                            (extend, gap_score.saturating_add(extend))
+                        }
+                        _ => {
+                            let score = open.saturating_add(extend);
+                            (score, total.saturating_add(score))
+                        }
+                    };
+                    gap = Some((step.clone(), gap_score));
+                    total = strategy.total_score(gap_score);
+                    score
+                }
+                _ => {
+                    total = strategy.total_score(total.saturating_add(extend));
+                    extend
+                }
+            };
+            cursor.apply_forwards_step(step.mask());
+            steps.push(ScoredStep { step, score, total });
+        }
+        Rescore {
+            score: total,
+            steps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{
+        AlignmentSet, Cigar, Cursor, EndGaps, InMemoryAlignmentMatrix, NeedlemanWunsch, SemiGlobal,
+        SmithWaterman,
+    };
+
+    const X: &[u8] = b"ACCGTTTGATTACAGGGGTACCA";
+    const Y: &[u8] = b"CCGTAGTTACTTCAGGGGCCA";
+
+    fn alignment_set<S: Strategy<Score = isize>>(
+        strategy: S,
+    ) -> AlignmentSet<InMemoryAlignmentMatrix> {
+        AlignmentSet::new(X.len(), Y.len(), strategy, |x, y| X[x] == Y[y]).unwrap()
+    }
+
+    fn assert_rescores<S: Strategy<Score = isize> + Clone>(strategy: S) {
+        let set = alignment_set(strategy.clone());
+        for alignment in [set.global_alignment(), set.local_alignment()].iter() {
+            let rescore = alignment.rescore(X.len(), Y.len(), &strategy, |x, y| X[x] == Y[y]);
+            assert_eq!(rescore.score(), alignment.score());
+            assert_eq!(rescore.steps().len(), alignment.len());
+        }
+    }
+
+    #[test]
+    fn rescore_works() {
+        assert_rescores(NeedlemanWunsch::new(1, -1, -1, -1));
+        assert_rescores(NeedlemanWunsch::affine(1, -1, -3, -1));
+        assert_rescores(SmithWaterman::new(2, -1, -1, -1));
+        assert_rescores(SmithWaterman::affine(2, -1, -2, -1));
+        let end_gaps = EndGaps::X_LEADING | EndGaps::X_TRAILING;
+        assert_rescores(SemiGlobal::new(
+            NeedlemanWunsch::new(1, -1, -1, -1),
+            end_gaps,
+        ));
+        assert_rescores(SemiGlobal::new(
+            NeedlemanWunsch::affine(1, -1, -3, -1),
+            end_gaps,
+        ));
+    }
+
+    #[test]
+    fn breakdown_works() {
+        let cigar: Cigar = "2M2D1M1I".parse().unwrap();
+        let alignment = Alignment::from_cigar(&cigar, Cursor { x: 0, y: 0 }, 0);
+        let x = b"ACGTA";
+        let y = b"AGAT";
+        let f = |i: usize, j: usize| x[i] == y[j];
+
+        let strategy = NeedlemanWunsch::affine(1, -1, -3, -1);
+        let rescore = alignment.rescore(x.len(), y.len(), &strategy, f);
+        let scores: Vec<(isize, isize)> =
+            rescore.steps().iter().map(|s| (s.score, s.total)).collect();
+        assert_eq!(
+            scores,
+            vec![(1, 1), (-1, 0), (-4, -4), (-1, -5), (1, -4), (-4, -8)]
+        );
+        assert_eq!(rescore.score(), -8);
+
+        let strategy = SmithWaterman::affine(1, -1, -3, -1);
+        let rescore = alignment.rescore(x.len(), y.len(), &strategy, f);
+        let totals: Vec<isize> = rescore.steps().iter().map(|s| s.total).collect();
+        assert_eq!(totals, vec![1, 0, 0, 0, 1, 0]);
+    }
+}
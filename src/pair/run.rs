@@ -3,18 +3,54 @@ use std::ops::Range;
 use crate::pair::step_mask::StepMask;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum Run {
-    Align { x: Range<usize>, y: Range<usize> },
-    Delete { x: Range<usize> },
-    Insert { y: Range<usize> },
+    Align {
+        x: Range<usize>,
+        y: Range<usize>,
+    },
+    /// A run of equal symbols, only produced by classified alignments.
+    Match {
+        x: Range<usize>,
+        y: Range<usize>,
+    },
+    /// A run of unequal symbols, only produced by classified alignments.
+    Mismatch {
+        x: Range<usize>,
+        y: Range<usize>,
+    },
+    Delete {
+        x: Range<usize>,
+    },
+    Insert {
+        y: Range<usize>,
+    },
 }
 
 impl Run {
     pub fn mask(&self) -> StepMask {
         match *self {
             Run::Align { x: _, y: _ } => StepMask::ALIGN,
+            Run::Match { x: _, y: _ } => StepMask::ALIGN | StepMask::MATCH,
+            Run::Mismatch { x: _, y: _ } => StepMask::ALIGN,
             Run::Delete { x: _ } => StepMask::DELETE,
             Run::Insert { y: _ } => StepMask::INSERT,
         }
     }
+
+    /// The number of steps in the run.
+    pub fn len(&self) -> usize {
+        match *self {
+            Run::Align { ref x, .. }
+            | Run::Match { ref x, .. }
+            | Run::Mismatch { ref x, .. }
+            | Run::Delete { ref x } => x.len(),
+            Run::Insert { ref y } => y.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
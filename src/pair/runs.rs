@@ -1,7 +1,7 @@
 use std::iter::Peekable;
+use std::ops::Range;
 
 use crate::pair::run::Run;
-use crate::pair::step::Step;
 use crate::pair::steps::Steps;
 
 pub struct Runs<'a> {
@@ -19,43 +19,59 @@ impl<'a> Iterator for Runs<'a> {
 
     fn next(&mut self) -> Option<Run> {
         let inner = &mut self.inner;
-        let run = inner.peek().map(|step| step.to_run());
-        run.map(|mut run| {
-            let mask = run.mask();
-            let mut peekable = inner.peekable();
-            let mut cautious_take_while = || {
-                let is_match = match peekable.peek() {
-                    Some(step) => step.mask() == mask,
-                    None => false,
-                };
-                if is_match {
-                    peekable.next()
-                } else {
-                    None
-                }
+        let mut run = inner.next()?.to_run();
+        let mask = run.mask();
+        // Steps of a run are contiguous, so each one extends it by one:
+        while inner.next_if(|step| step.mask() == mask).is_some() {
+            let extend = |range: Range<usize>| (range.start)..(range.end + 1);
+            run = match run {
+                Run::Align { x, y } => Run::Align {
+                    x: extend(x),
+                    y: extend(y),
+                },
+                Run::Match { x, y } => Run::Match {
+                    x: extend(x),
+                    y: extend(y),
+                },
+                Run::Mismatch { x, y } => Run::Mismatch {
+                    x: extend(x),
+                    y: extend(y),
+                },
+                Run::Delete { x } => Run::Delete { x: extend(x) },
+                Run::Insert { y } => Run::Insert { y: extend(y) },
             };
-            while let Some(step) = cautious_take_while() {
-                run = match (run, step) {
-                    (
-                        Run::Align { x: run_x, y: run_y },
-                        Step::Align {
-                            x: step_x,
-                            y: step_y,
-                        },
-                    ) => Run::Align {
-                        x: (run_x.start)..(step_x + 1),
-                        y: (run_y.start)..(step_y + 1),
-                    },
-                    (Run::Delete { x: run_x }, Step::Delete { x: step_x }) => Run::Delete {
-                        x: (run_x.start)..(step_x + 1),
-                    },
-                    (Run::Insert { y: run_y }, Step::Insert { y: step_y }) => Run::Insert {
-                        y: (run_y.start)..(step_y + 1),
-                    },
-                    _ => unreachable!(),
-                }
-            }
-            run
-        })
+        }
+        Some(run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pair::alignment::Alignment;
+    use crate::pair::cursor::Cursor;
+    use crate::pair::run::Run;
+    use crate::pair::step_mask::StepMask;
+
+    #[test]
+    fn runs_work() {
+        let steps = vec![
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::DELETE,
+            StepMask::INSERT,
+            StepMask::INSERT,
+            StepMask::ALIGN,
+        ];
+        let alignment = Alignment::new(Cursor { x: 1, y: 2 }, steps, 0);
+        let runs: Vec<Run> = alignment.runs().collect();
+        assert_eq!(
+            runs,
+            vec![
+                Run::Align { x: 1..3, y: 2..4 },
+                Run::Delete { x: 3..4 },
+                Run::Insert { y: 4..6 },
+                Run::Align { x: 4..5, y: 6..7 },
+            ]
+        );
     }
 }
@@ -0,0 +1,199 @@
+use std::io::{self, Write};
+
+use crate::pair::alignment::Alignment;
+use crate::pair::run::Run;
+use crate::pair::score::Score;
+
+/// A read-to-reference alignment to be written as a SAM record,
+/// with `x` being the reference and `y` the query.
+pub struct SamRecord<'a, N = isize> {
+    pub query_name: &'a str,
+    pub reference_name: &'a str,
+    pub reference: &'a [u8],
+    pub query: &'a [u8],
+    pub alignment: &'a Alignment<N>,
+    /// The bitwise FLAG, with `0x4` (unmapped) being added for empty alignments.
+    pub flags: u16,
+    /// The mapping quality, `255` if unavailable.
+    pub mapq: u8,
+}
+
+impl<'a, N: Score> SamRecord<'a, N> {
+    pub fn new(
+        query_name: &'a str,
+        reference_name: &'a str,
+        reference: &'a [u8],
+        query: &'a [u8],
+        alignment: &'a Alignment<N>,
+    ) -> SamRecord<'a, N> {
+        SamRecord {
+            query_name,
+            reference_name,
+            reference,
+            query,
+            alignment,
+            flags: 0,
+            mapq: 255,
+        }
+    }
+
+    // Returns the edit distance (`NM`) and mismatching positions (`MD`).
+    fn edits(&self) -> (usize, String) {
+        let (reference, query) = (self.reference, self.query);
+        let mut distance = 0;
+        let mut md = String::new();
+        let mut matches = 0;
+        for run in self.alignment.runs() {
+            match run {
+                Run::Align { x, y } | Run::Match { x, y } | Run::Mismatch { x, y } => {
+                    for (i, j) in x.zip(y) {
+                        if reference[i] == query[j] {
+                            matches += 1;
+                        } else {
+                            distance += 1;
+                            md.push_str(&matches.to_string());
+                            md.push(reference[i] as char);
+                            matches = 0;
+                        }
+                    }
+                }
+                Run::Delete { x } => {
+                    distance += x.len();
+                    md.push_str(&matches.to_string());
+                    md.push('^');
+                    md.extend(reference[x].iter().map(|&c| c as char));
+                    matches = 0;
+                }
+                Run::Insert { y } => distance += y.len(),
+            }
+        }
+        md.push_str(&matches.to_string());
+        (distance, md)
+    }
+}
+
+/// Writes SAM headers and records.
+pub struct SamWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> SamWriter<W> {
+    pub fn new(inner: W) -> SamWriter<W> {
+        SamWriter { inner }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes the `@HD` line, an `@SQ` line per `(name, length)` reference
+    /// and a `@PG` line for seal.
+    pub fn write_header(&mut self, references: &[(&str, usize)]) -> io::Result<()> {
+        writeln!(self.inner, "@HD\tVN:1.6\tSO:unsorted")?;
+        for (name, len) in references {
+            writeln!(self.inner, "@SQ\tSN:{}\tLN:{}", name, len)?;
+        }
+        writeln!(
+            self.inner,
+            "@PG\tID:seal\tPN:seal\tVN:{}",
+            env!("CARGO_PKG_VERSION")
+        )
+    }
+
+    /// Writes a record, with `POS` and `CIGAR` derived from the alignment
+    /// and `NM`, `MD` and `AS` tags.
+    pub fn write_record<N>(&mut self, record: &SamRecord<'_, N>) -> io::Result<()>
+    where
+        N: Score,
+    {
+        let alignment = record.alignment;
+        let seq: String = record.query.iter().map(|&c| c as char).collect();
+        let seq = if seq.is_empty() { "*".to_string() } else { seq };
+        if alignment.is_empty() {
+            return writeln!(
+                self.inner,
+                "{}\t{}\t*\t0\t0\t*\t*\t0\t0\t{}\t*",
+                record.query_name,
+                record.flags | 0x4,
+                seq
+            );
+        }
+
+        let (distance, md) = record.edits();
+        write!(
+            self.inner,
+            "{}\t{}\t{}\t{}\t{}\t{}\t*\t0\t0\t{}\t*\tNM:i:{}\tMD:Z:{}",
+            record.query_name,
+            record.flags,
+            record.reference_name,
+            alignment.origin().x + 1,
+            record.mapq,
+            alignment.cigar(record.query.len()),
+            seq,
+            distance,
+            md
+        )?;
+        write_score_tag(&mut self.inner, alignment.score())?;
+        writeln!(self.inner)
+    }
+}
+
+// Writes an `AS:i` tag, omitting scores without an integer value (see `Score::to_i64()`).
+pub(crate) fn write_score_tag<W: Write, N: Score>(writer: &mut W, score: N) -> io::Result<()> {
+    match score.to_i64() {
+        Some(score) => write!(writer, "\tAS:i:{}", score),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::{AlignmentSet, InMemoryAlignmentMatrix, SmithWaterman};
+
+    #[test]
+    fn sam_works() {
+        let reference = b"TTTTGATTACAGCGG";
+        let query = b"CCGATACAGTGG";
+        let strategy = SmithWaterman::new(2, -1, -1, -1);
+        let set: AlignmentSet<InMemoryAlignmentMatrix> =
+            AlignmentSet::new(reference.len(), query.len(), strategy, |x, y| {
+                reference[x] == query[y]
+            })
+            .unwrap();
+        let alignment = set.local_alignment();
+
+        let mut writer = SamWriter::new(Vec::new());
+        writer.write_header(&[("chr1", reference.len())]).unwrap();
+        let mut record = SamRecord::new("read1", "chr1", reference, query, &alignment);
+        record.mapq = 60;
+        writer.write_record(&record).unwrap();
+
+        let empty = Alignment::new(*alignment.origin(), vec![], 0);
+        let record = SamRecord::new("read2", "chr1", reference, b"ACGT", &empty);
+        writer.write_record(&record).unwrap();
+
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "@HD\tVN:1.6\tSO:unsorted");
+        assert_eq!(lines[1], "@SQ\tSN:chr1\tLN:15");
+        assert!(lines[2].starts_with("@PG\tID:seal\tPN:seal\tVN:"));
+        assert_eq!(
+            lines[3],
+            "read1\t0\tchr1\t5\t60\t2S3M1D7M\t*\t0\t0\tCCGATACAGTGG\t*\tNM:i:2\tMD:Z:3^T4C2\tAS:i:16"
+        );
+        assert_eq!(lines[4], "read2\t4\t*\t0\t0\t*\t*\t0\t0\tACGT\t*");
+    }
+
+    #[test]
+    fn score_tags_work() {
+        let mut output = vec![];
+        write_score_tag(&mut output, -3i16).unwrap();
+        write_score_tag(&mut output, 16.0f32).unwrap();
+        write_score_tag(&mut output, 16.4f64).unwrap();
+        write_score_tag(&mut output, f64::NAN).unwrap();
+        write_score_tag(&mut output, f32::NEG_INFINITY).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, "\tAS:i:-3\tAS:i:16\tAS:i:16");
+    }
+}
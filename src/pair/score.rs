@@ -0,0 +1,157 @@
+use std::fmt::Debug;
+
+/// A numeric alignment score.
+///
+/// Integer scores saturate instead of overflowing,
+/// while floating-point scores follow regular IEEE 754 arithmetic.
+pub trait Score: Copy + Debug + PartialOrd {
+    /// The score of an empty alignment.
+    const ZERO: Self;
+    /// The score of an unreachable cell, which no alignment ever improves on.
+    const MIN: Self;
+
+    fn saturating_add(self, other: Self) -> Self;
+
+    /// The score of `count` repetitions of `self`, e.g. for a gap of length `count`.
+    fn times(self, count: usize) -> Self;
+
+    /// The score as an integer (rounding floats), e.g. for SAM's `AS:i` tag,
+    /// or `None` if it isn't representable.
+    fn to_i64(self) -> Option<i64> {
+        None
+    }
+}
+
+macro_rules! impl_integer_score {
+    ($($t:ty),*) => {
+        $(
+            impl Score for $t {
+                const ZERO: Self = 0;
+                const MIN: Self = <$t>::MIN;
+
+                fn saturating_add(self, other: Self) -> Self {
+                    <$t>::saturating_add(self, other)
+                }
+
+                fn times(self, count: usize) -> Self {
+                    let count = <$t>::try_from(count).unwrap_or(<$t>::MAX);
+                    self.saturating_mul(count)
+                }
+
+                fn to_i64(self) -> Option<i64> {
+                    i64::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_float_score {
+    ($($t:ty),*) => {
+        $(
+            impl Score for $t {
+                const ZERO: Self = 0.0;
+                const MIN: Self = <$t>::NEG_INFINITY;
+
+                fn saturating_add(self, other: Self) -> Self {
+                    self + other
+                }
+
+                fn times(self, count: usize) -> Self {
+                    self * (count as $t)
+                }
+
+                fn to_i64(self) -> Option<i64> {
+                    let rounded = self.round();
+                    let in_range = (rounded >= i64::MIN as $t) && (rounded < i64::MAX as $t);
+                    if in_range {
+                        Some(rounded as i64)
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_score!(i16, i32, i64, isize);
+impl_float_score!(f32, f64);
+
+/// A score that can be stored in the header of a persisted alignment set.
+pub trait PersistableScore: Score {
+    /// A tag distinguishing the score type.
+    const TAG: u8;
+
+    fn to_bits(self) -> u64;
+    fn from_bits(bits: u64) -> Self;
+}
+
+// Integers are stored as the bits of their `i64` value, floats as those of their `f64` value.
+macro_rules! impl_persistable_integer_score {
+    ($($t:ty => $tag:expr),*) => {
+        $(
+            impl PersistableScore for $t {
+                const TAG: u8 = $tag;
+
+                fn to_bits(self) -> u64 {
+                    (self as i64) as u64
+                }
+
+                fn from_bits(bits: u64) -> Self {
+                    (bits as i64) as $t
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_persistable_float_score {
+    ($($t:ty => $tag:expr),*) => {
+        $(
+            impl PersistableScore for $t {
+                const TAG: u8 = $tag;
+
+                fn to_bits(self) -> u64 {
+                    (self as f64).to_bits()
+                }
+
+                fn from_bits(bits: u64) -> Self {
+                    f64::from_bits(bits) as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_persistable_integer_score!(i16 => 1, i32 => 2, i64 => 3, isize => 4);
+impl_persistable_float_score!(f32 => 5, f64 => 6);
+
+/// The greater of two scores, preferring `a` if they're unordered.
+pub(crate) fn max<N: Score>(a: N, b: N) -> N {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_saturate() {
+        assert_eq!(i16::MIN.saturating_add(-1), i16::MIN);
+        assert_eq!(Score::saturating_add(i16::MAX, 1), i16::MAX);
+        assert_eq!((-2i16).times(100_000), i16::MIN);
+        assert_eq!((-2i32).times(3), -6);
+    }
+
+    #[test]
+    fn floats_work() {
+        assert_eq!(<f64 as Score>::MIN.saturating_add(10.0), f64::NEG_INFINITY);
+        assert_eq!((-0.5f32).times(3), -1.5);
+        assert_eq!(max(1.0, 2.0), 2.0);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::pair::end_gaps::EndGaps;
+use crate::pair::step_mask::StepMask;
+use crate::pair::strategy::Strategy;
+
+/// Wraps a global strategy, making the given end gaps free of cost.
+///
+/// Use `EndGaps::X_LEADING | EndGaps::X_TRAILING` for fitting `y` into `x`
+/// and `EndGaps::X_LEADING | EndGaps::Y_TRAILING` for overlapping
+/// a suffix of `x` with a prefix of `y`.
+#[derive(Clone, Debug)]
+pub struct SemiGlobal<S> {
+    strategy: S,
+    end_gaps: EndGaps,
+}
+
+impl<S> SemiGlobal<S> {
+    pub fn new(strategy: S, end_gaps: EndGaps) -> SemiGlobal<S> {
+        SemiGlobal { strategy, end_gaps }
+    }
+}
+
+impl<S> Strategy for SemiGlobal<S>
+where
+    S: Strategy,
+{
+    type Score = S::Score;
+
+    fn match_score(&self) -> S::Score {
+        self.strategy.match_score()
+    }
+
+    fn mismatch_score(&self) -> S::Score {
+        self.strategy.mismatch_score()
+    }
+
+    fn insert_score(&self) -> S::Score {
+        self.strategy.insert_score()
+    }
+
+    fn delete_score(&self) -> S::Score {
+        self.strategy.delete_score()
+    }
+
+    fn insert_open_score(&self) -> S::Score {
+        self.strategy.insert_open_score()
+    }
+
+    fn delete_open_score(&self) -> S::Score {
+        self.strategy.delete_open_score()
+    }
+
+    fn total_score(&self, score: S::Score) -> S::Score {
+        self.strategy.total_score(score)
+    }
+
+    fn step_mask(&self, align: S::Score, insert: S::Score, delete: S::Score) -> StepMask {
+        self.strategy.step_mask(align, insert, delete)
+    }
+
+    fn free_end_gaps(&self) -> EndGaps {
+        self.strategy.free_end_gaps() | self.end_gaps
+    }
+
+    fn is_local(&self) -> bool {
+        self.strategy.is_local()
+    }
+}
@@ -1,55 +1,79 @@
-use std::cmp;
-
+use crate::pair::score::{self, Score};
 use crate::pair::step_mask::StepMask;
 use crate::pair::strategy::Strategy;
 
 #[derive(Clone, Debug)]
-pub struct SmithWaterman {
-    equal: isize,
-    align: isize,
-    insert: isize,
-    delete: isize,
+pub struct SmithWaterman<N = isize> {
+    equal: N,
+    align: N,
+    insert: N,
+    delete: N,
+    insert_open: N,
+    delete_open: N,
 }
 
-impl SmithWaterman {
-    pub fn new(equal: isize, align: isize, insert: isize, delete: isize) -> SmithWaterman {
+impl<N: Score> SmithWaterman<N> {
+    pub fn new(equal: N, align: N, insert: N, delete: N) -> SmithWaterman<N> {
         SmithWaterman {
             equal,
             align,
             insert,
             delete,
+            insert_open: N::ZERO,
+            delete_open: N::ZERO,
+        }
+    }
+
+    pub fn affine(equal: N, align: N, open: N, extend: N) -> SmithWaterman<N> {
+        SmithWaterman {
+            equal,
+            align,
+            insert: extend,
+            delete: extend,
+            insert_open: open,
+            delete_open: open,
         }
     }
 }
 
-impl Strategy for SmithWaterman {
-    fn match_score(&self) -> isize {
+impl<N: Score> Strategy for SmithWaterman<N> {
+    type Score = N;
+
+    fn match_score(&self) -> N {
         self.equal
     }
 
-    fn mismatch_score(&self) -> isize {
+    fn mismatch_score(&self) -> N {
         self.align
     }
 
-    fn insert_score(&self) -> isize {
+    fn insert_score(&self) -> N {
         self.insert
     }
 
-    fn delete_score(&self) -> isize {
+    fn delete_score(&self) -> N {
         self.delete
     }
 
-    fn total_score(&self, score: isize) -> isize {
-        if score >= 0 {
-            score
-        } else {
-            0
-        }
+    fn insert_open_score(&self) -> N {
+        self.insert_open
+    }
+
+    fn delete_open_score(&self) -> N {
+        self.delete_open
+    }
+
+    fn total_score(&self, score: N) -> N {
+        score::max(score, N::ZERO)
+    }
+
+    fn is_local(&self) -> bool {
+        true
     }
 
-    fn step_mask(&self, align: isize, insert: isize, delete: isize) -> StepMask {
-        if cmp::max(cmp::max(align, insert), delete) > 0 {
-            StepMask::from_scores(align, insert, delete)
+    fn step_mask(&self, align: N, insert: N, delete: N) -> StepMask {
+        if score::max(score::max(align, insert), delete) > N::ZERO {
+            StepMask::from_scores(align, delete, insert)
         } else {
             StepMask::STOP
         }
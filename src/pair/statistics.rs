@@ -0,0 +1,204 @@
+use crate::pair::alignment::Alignment;
+use crate::pair::run::Run;
+use crate::pair::score::Score;
+
+/// Summary statistics of an alignment of `x` and `y`.
+///
+/// Percentages are in `0.0..=100.0` and are `0.0` for empty denominators.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Statistics {
+    length: usize,
+    identities: usize,
+    similarities: usize,
+    gap_openings: usize,
+    gap_length: usize,
+    x_aligned: usize,
+    y_aligned: usize,
+    x_len: usize,
+    y_len: usize,
+}
+
+impl Statistics {
+    /// Computes the statistics of `alignment` of sequences of length `x_len` and `y_len`,
+    /// with `is_equal(x, y)` telling whether the symbols at `x` and `y` are identical.
+    ///
+    /// Without a scoring function only identical symbols count as similar.
+    pub fn new<N, F>(alignment: &Alignment<N>, x_len: usize, y_len: usize, is_equal: F) -> Self
+    where
+        N: Score,
+        F: Fn(usize, usize) -> bool,
+    {
+        Self::with_scores(alignment, x_len, y_len, is_equal, |_, _| 0)
+    }
+
+    /// Like `new`, with pairs of symbols also counting as similar
+    /// if `score(x, y)` (e.g. from a substitution matrix) is positive.
+    pub fn with_scores<N, F, G>(
+        alignment: &Alignment<N>,
+        x_len: usize,
+        y_len: usize,
+        is_equal: F,
+        score: G,
+    ) -> Self
+    where
+        N: Score,
+        F: Fn(usize, usize) -> bool,
+        G: Fn(usize, usize) -> isize,
+    {
+        let mut statistics = Statistics {
+            length: alignment.len(),
+            identities: 0,
+            similarities: 0,
+            gap_openings: 0,
+            gap_length: 0,
+            x_aligned: 0,
+            y_aligned: 0,
+            x_len,
+            y_len,
+        };
+        for run in alignment.runs() {
+            match run {
+                Run::Align { x, y } | Run::Match { x, y } | Run::Mismatch { x, y } => {
+                    for (x, y) in x.zip(y) {
+                        if is_equal(x, y) {
+                            statistics.identities += 1;
+                            statistics.similarities += 1;
+                        } else if score(x, y) > 0 {
+                            statistics.similarities += 1;
+                        }
+                        statistics.x_aligned += 1;
+                        statistics.y_aligned += 1;
+                    }
+                }
+                Run::Delete { x } => {
+                    statistics.gap_openings += 1;
+                    statistics.gap_length += x.len();
+                    statistics.x_aligned += x.len();
+                }
+                Run::Insert { y } => {
+                    statistics.gap_openings += 1;
+                    statistics.gap_length += y.len();
+                    statistics.y_aligned += y.len();
+                }
+            }
+        }
+        statistics
+    }
+
+    /// The number of alignment columns, including gaps.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// The number of columns with identical symbols.
+    pub fn identities(&self) -> usize {
+        self.identities
+    }
+
+    /// The number of columns with identical or similar symbols.
+    pub fn similarities(&self) -> usize {
+        self.similarities
+    }
+
+    /// The number of columns with non-identical symbols.
+    pub fn mismatches(&self) -> usize {
+        self.length - self.gap_length - self.identities
+    }
+
+    /// The number of gaps, with adjacent insertions and deletions counting separately.
+    pub fn gap_openings(&self) -> usize {
+        self.gap_openings
+    }
+
+    /// The number of gap columns.
+    pub fn gap_length(&self) -> usize {
+        self.gap_length
+    }
+
+    /// Identities per alignment column, including gaps
+    /// (as reported by BLAST and EMBOSS).
+    pub fn identity(&self) -> f64 {
+        percent(self.identities, self.length)
+    }
+
+    /// Identities per aligned (i.e. non-gap) column, ignoring gaps altogether.
+    pub fn aligned_identity(&self) -> f64 {
+        percent(self.identities, self.length - self.gap_length)
+    }
+
+    /// Identities per symbol of the shorter sequence.
+    pub fn shorter_identity(&self) -> f64 {
+        percent(self.identities, self.x_len.min(self.y_len))
+    }
+
+    /// Identical or similar symbols per alignment column, including gaps
+    /// (as reported by BLAST and EMBOSS).
+    pub fn similarity(&self) -> f64 {
+        percent(self.similarities, self.length)
+    }
+
+    /// The percentage of symbols of `x` covered by the alignment,
+    /// whether aligned or deleted.
+    pub fn x_coverage(&self) -> f64 {
+        percent(self.x_aligned, self.x_len)
+    }
+
+    /// The percentage of symbols of `y` covered by the alignment,
+    /// whether aligned or inserted.
+    pub fn y_coverage(&self) -> f64 {
+        percent(self.y_aligned, self.y_len)
+    }
+}
+
+fn percent(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    100.0 * (count as f64) / (total as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::cursor::Cursor;
+    use crate::pair::step_mask::StepMask;
+
+    #[test]
+    fn statistics_work() {
+        // x: AGATTACA--
+        // y:  GAT-ACKAA
+        let x = b"AGATTACA";
+        let y = b"GATACKAA";
+        let steps = vec![
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::DELETE,
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::ALIGN,
+            StepMask::INSERT,
+            StepMask::INSERT,
+        ];
+        let alignment = Alignment::new(Cursor { x: 1, y: 0 }, steps, 0);
+        let is_equal = |i: usize, j: usize| x[i] == y[j];
+        let score = |i: usize, j: usize| if (x[i], y[j]) == (b'A', b'K') { 1 } else { -1 };
+        let statistics = Statistics::with_scores(&alignment, x.len(), y.len(), is_equal, score);
+
+        assert_eq!(statistics.length(), 9);
+        assert_eq!(statistics.identities(), 5);
+        assert_eq!(statistics.similarities(), 6);
+        assert_eq!(statistics.mismatches(), 1);
+        assert_eq!(statistics.gap_openings(), 2);
+        assert_eq!(statistics.gap_length(), 3);
+        assert_eq!(statistics.identity(), 100.0 * 5.0 / 9.0);
+        assert_eq!(statistics.aligned_identity(), 100.0 * 5.0 / 6.0);
+        assert_eq!(statistics.shorter_identity(), 100.0 * 5.0 / 8.0);
+        assert_eq!(statistics.similarity(), 100.0 * 6.0 / 9.0);
+        assert_eq!(statistics.x_coverage(), 100.0 * 7.0 / 8.0);
+        assert_eq!(statistics.y_coverage(), 100.0);
+
+        let statistics = Statistics::new(&alignment, x.len(), y.len(), is_equal);
+        assert_eq!(statistics.similarities(), 5);
+    }
+}
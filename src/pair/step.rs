@@ -2,16 +2,37 @@ use crate::pair::run::Run;
 use crate::pair::step_mask::StepMask;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum Step {
-    Align { x: usize, y: usize },
-    Delete { x: usize },
-    Insert { y: usize },
+    Align {
+        x: usize,
+        y: usize,
+    },
+    /// An alignment of equal symbols, only produced by classified alignments.
+    Match {
+        x: usize,
+        y: usize,
+    },
+    /// An alignment of unequal symbols, only produced by classified alignments.
+    Mismatch {
+        x: usize,
+        y: usize,
+    },
+    Delete {
+        x: usize,
+    },
+    Insert {
+        y: usize,
+    },
 }
 
 impl Step {
     pub fn mask(&self) -> StepMask {
         match *self {
             Step::Align { x: _, y: _ } => StepMask::ALIGN,
+            Step::Match { x: _, y: _ } => StepMask::ALIGN | StepMask::MATCH,
+            Step::Mismatch { x: _, y: _ } => StepMask::ALIGN,
             Step::Delete { x: _ } => StepMask::DELETE,
             Step::Insert { y: _ } => StepMask::INSERT,
         }
@@ -23,6 +44,14 @@ impl Step {
                 x: (x)..(x + 1),
                 y: (y)..(y + 1),
             },
+            Step::Match { x, y } => Run::Match {
+                x: (x)..(x + 1),
+                y: (y)..(y + 1),
+            },
+            Step::Mismatch { x, y } => Run::Mismatch {
+                x: (x)..(x + 1),
+                y: (y)..(y + 1),
+            },
             Step::Delete { x } => Run::Delete { x: (x)..(x + 1) },
             Step::Insert { y } => Run::Insert { y: (y)..(y + 1) },
         }
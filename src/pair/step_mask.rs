@@ -1,31 +1,77 @@
 #![allow(dead_code)]
-use std::cmp;
+use crate::pair::score::{self, Score};
 
 use bitflags::bitflags;
 
 bitflags! {
     pub struct StepMask: u8 {
         const STOP   = 0b00000000;
+        // Marks aligned steps of equal symbols in classified alignments:
+        const MATCH  = 0b00000001;
         const ALIGN  = 0b00000010;
         const DELETE = 0b00000100;
         const INSERT = 0b00001000;
+
+        // Gap state transitions, only used by strategies with affine gaps:
+        const DELETE_EXTEND = 0b00010000;
+        const INSERT_EXTEND = 0b00100000;
+        const DELETE_OPEN   = 0b01000000;
+        const INSERT_OPEN   = 0b10000000;
     }
 }
 
 impl StepMask {
-    pub fn from_scores(align: isize, delete: isize, insert: isize) -> StepMask {
+    pub fn from_scores<N: Score>(align: N, delete: N, insert: N) -> StepMask {
         let mut step_mask = StepMask::empty();
-        if align >= cmp::max(delete, insert) {
+        if align >= score::max(delete, insert) {
             step_mask.insert(StepMask::ALIGN);
         }
-        if delete >= cmp::max(align, insert) {
+        if delete >= score::max(align, insert) {
             step_mask.insert(StepMask::DELETE);
         }
-        if insert >= cmp::max(align, delete) {
+        if insert >= score::max(align, delete) {
             step_mask.insert(StepMask::INSERT);
         }
         step_mask
     }
+
+    pub fn direction(&self) -> StepMask {
+        *self & (StepMask::ALIGN | StepMask::DELETE | StepMask::INSERT)
+    }
+
+    pub(crate) fn from_gap_scores<N: Score>(
+        extend: N,
+        open: N,
+        extend_mask: StepMask,
+        open_mask: StepMask,
+    ) -> (StepMask, N) {
+        let score = score::max(extend, open);
+        let mut step_mask = StepMask::empty();
+        if extend == score {
+            step_mask.insert(extend_mask);
+        }
+        if open == score {
+            step_mask.insert(open_mask);
+        }
+        (step_mask, score)
+    }
+}
+
+// Serialized as its bits, rejecting unknown ones:
+#[cfg(feature = "serde")]
+impl serde::Serialize for StepMask {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.bits())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for StepMask {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = <u8 as serde::Deserialize>::deserialize(deserializer)?;
+        StepMask::from_bits(bits)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid step mask: {:#010b}", bits)))
+    }
 }
 
 #[cfg(test)]
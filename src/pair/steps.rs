@@ -7,11 +7,17 @@ use crate::pair::step_mask::StepMask;
 pub struct Steps<'a> {
     pub inner: Iter<'a, StepMask>,
     pub cursor: Cursor,
+    /// Whether to yield `Match` and `Mismatch`, rather than `Align` steps.
+    pub classified: bool,
 }
 
 impl<'a> Steps<'a> {
     pub fn new(inner: Iter<'a, StepMask>, cursor: Cursor) -> Self {
-        Steps { inner, cursor }
+        Steps {
+            inner,
+            cursor,
+            classified: false,
+        }
     }
 }
 
@@ -22,11 +28,11 @@ impl<'a> Iterator for Steps<'a> {
         self.inner.next().and_then(|mask| {
             let cursor = self.cursor;
             self.cursor.apply_forwards_step(*mask);
-            match *mask {
-                StepMask::ALIGN => Some(Step::Align {
-                    x: cursor.x,
-                    y: cursor.y,
-                }),
+            let (x, y) = (cursor.x, cursor.y);
+            match mask.direction() {
+                StepMask::ALIGN if !self.classified => Some(Step::Align { x, y }),
+                StepMask::ALIGN if mask.contains(StepMask::MATCH) => Some(Step::Match { x, y }),
+                StepMask::ALIGN => Some(Step::Mismatch { x, y }),
                 StepMask::DELETE => Some(Step::Delete { x: cursor.x }),
                 StepMask::INSERT => Some(Step::Insert { y: cursor.y }),
                 _ => None,
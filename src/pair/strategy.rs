@@ -1,10 +1,39 @@
-use crate::pair::StepMask;
+use crate::pair::{EndGaps, Score, StepMask};
 
 pub trait Strategy {
-    fn match_score(&self) -> isize;
-    fn mismatch_score(&self) -> isize;
-    fn insert_score(&self) -> isize;
-    fn delete_score(&self) -> isize;
-    fn total_score(&self, strategy: isize) -> isize;
-    fn step_mask(&self, align: isize, insert: isize, delete: isize) -> StepMask;
+    type Score: Score;
+
+    fn match_score(&self) -> Self::Score;
+    fn mismatch_score(&self) -> Self::Score;
+    fn insert_score(&self) -> Self::Score;
+    fn delete_score(&self) -> Self::Score;
+    fn total_score(&self, strategy: Self::Score) -> Self::Score;
+    fn step_mask(&self, align: Self::Score, insert: Self::Score, delete: Self::Score) -> StepMask;
+
+    /// Additional score for opening an insertion,
+    /// making an insertion of length `n` score `insert_open_score() + n * insert_score()`.
+    fn insert_open_score(&self) -> Self::Score {
+        Self::Score::ZERO
+    }
+
+    /// Additional score for opening a deletion,
+    /// making a deletion of length `n` score `delete_open_score() + n * delete_score()`.
+    fn delete_open_score(&self) -> Self::Score {
+        Self::Score::ZERO
+    }
+
+    /// End gaps that don't contribute to the global score.
+    fn free_end_gaps(&self) -> EndGaps {
+        EndGaps::empty()
+    }
+
+    /// Whether `total_score()` clamps scores at zero, making alignments local.
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    fn has_affine_gaps(&self) -> bool {
+        (self.insert_open_score() != Self::Score::ZERO)
+            || (self.delete_open_score() != Self::Score::ZERO)
+    }
 }
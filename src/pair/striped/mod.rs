@@ -0,0 +1,237 @@
+use std::convert::TryFrom;
+
+use crate::pair::alignment_scores::AlignmentScores;
+use crate::pair::smith_waterman::SmithWaterman;
+use crate::pair::strategy::Strategy;
+use crate::pair::substitution::Substitution;
+
+#[cfg(target_arch = "x86_64")]
+mod x86;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Isa {
+    Sse2,
+    Avx2,
+}
+
+impl Isa {
+    fn detect() -> Option<Isa> {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return Some(Isa::Avx2);
+            }
+            if is_x86_feature_detected!("sse2") {
+                return Some(Isa::Sse2);
+            }
+        }
+        None
+    }
+
+    fn lanes(&self) -> usize {
+        match self {
+            Isa::Sse2 => 8,
+            Isa::Avx2 => 16,
+        }
+    }
+}
+
+// The query's substitution scores against each possible target symbol,
+// striped across `segments` vectors of `lanes` scores each.
+struct Profile {
+    isa: Isa,
+    lanes: usize,
+    segments: usize,
+    scores: Vec<i16>,
+    max_score: i16,
+    insert_extend: i16,
+    insert_open: i16,
+    delete_extend: i16,
+    delete_open: i16,
+}
+
+impl Profile {
+    fn new<F, R>(query: &[u8], strategy: &SmithWaterman, f: &F, isa: Isa) -> Option<Profile>
+    where
+        F: Fn(u8, u8) -> R,
+        R: Substitution<isize>,
+    {
+        let insert_extend = strategy.insert_score();
+        let delete_extend = strategy.delete_score();
+        let insert_open = strategy.insert_open_score();
+        let delete_open = strategy.delete_open_score();
+
+        // The lazy-F loop relies on gaps strictly decreasing the score:
+        if query.is_empty() || (insert_extend >= 0) || (delete_extend >= 0) {
+            return None;
+        }
+        if (insert_open > 0) || (delete_open > 0) {
+            return None;
+        }
+
+        let lanes = isa.lanes();
+        let segments = query.len().div_ceil(lanes);
+        let mut scores = Vec::with_capacity(256 * segments * lanes);
+        let mut max_score = 0;
+        for symbol in 0..=u8::MAX {
+            for segment in 0..segments {
+                for lane in 0..lanes {
+                    let score = match query.get(lane * segments + segment) {
+                        Some(&x) => i16::try_from(f(x, symbol).score(strategy)).ok()?,
+                        None => i16::MIN,
+                    };
+                    max_score = max_score.max(score);
+                    scores.push(score);
+                }
+            }
+        }
+
+        Some(Profile {
+            isa,
+            lanes,
+            segments,
+            scores,
+            max_score,
+            insert_extend: i16::try_from(insert_extend).ok()?,
+            insert_open: i16::try_from(insert_open + insert_extend).ok()?,
+            delete_extend: i16::try_from(delete_extend).ok()?,
+            delete_open: i16::try_from(delete_open + delete_extend).ok()?,
+        })
+    }
+
+    // Returns `None` if the score might have saturated.
+    fn local_score(&self, target: &[u8]) -> Option<isize> {
+        #[cfg(target_arch = "x86_64")]
+        {
+            // Safety: the instruction set was detected as available when creating the profile.
+            let score = unsafe {
+                match self.isa {
+                    Isa::Sse2 => x86::local_score_sse2(self, target),
+                    Isa::Avx2 => x86::local_score_avx2(self, target),
+                }
+            };
+            if score < i16::MAX - self.max_score {
+                return Some(score as isize);
+            }
+        }
+        let _ = target;
+        None
+    }
+}
+
+/// Score-only local alignment of a single query `x` against many targets `y`,
+/// using Farrar's striped algorithm with SSE2 or AVX2, as detected at runtime.
+///
+/// Unlike `AlignmentSet` the closure compares symbols, rather than indices.
+///
+/// The scores are identical to those of `AlignmentScores` with the same strategy.
+/// Falls back to the scalar implementation if no suitable instruction set is available,
+/// gap scores aren't negative, or the scores exceed the range of `i16`.
+pub struct StripedSmithWaterman<F> {
+    query: Vec<u8>,
+    strategy: SmithWaterman,
+    f: F,
+    profile: Option<Profile>,
+}
+
+impl<F> StripedSmithWaterman<F> {
+    pub fn new<R>(query: &[u8], strategy: SmithWaterman, f: F) -> StripedSmithWaterman<F>
+    where
+        F: Fn(u8, u8) -> R,
+        R: Substitution<isize>,
+    {
+        let profile = Isa::detect().and_then(|isa| Profile::new(query, &strategy, &f, isa));
+        StripedSmithWaterman {
+            query: query.to_vec(),
+            strategy,
+            f,
+            profile,
+        }
+    }
+
+    /// Whether scores are computed using SIMD instructions.
+    pub fn is_vectorized(&self) -> bool {
+        self.profile.is_some()
+    }
+
+    pub fn local_score<R>(&self, target: &[u8]) -> isize
+    where
+        F: Fn(u8, u8) -> R,
+        R: Substitution<isize>,
+    {
+        if let Some(score) = self.profile.as_ref().and_then(|p| p.local_score(target)) {
+            return score;
+        }
+        let (query, f) = (&self.query, &self.f);
+        let strategy = self.strategy.clone();
+        let scores = AlignmentScores::new(query.len(), target.len(), strategy, |x, y| {
+            f(query[x], target[y])
+        });
+        scores.local_score()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pair::test_utils::sequence;
+    use crate::pair::BLOSUM62;
+
+    fn assert_matches_scalar<F, R>(query: &[u8], target: &[u8], strategy: SmithWaterman, f: F)
+    where
+        F: Fn(u8, u8) -> R + Copy,
+        R: Substitution<isize>,
+    {
+        let expected = AlignmentScores::new(query.len(), target.len(), strategy.clone(), |x, y| {
+            f(query[x], target[y])
+        });
+        let striped = StripedSmithWaterman::new(query, strategy.clone(), f);
+        assert_eq!(striped.local_score(target), expected.local_score());
+
+        for isa in [Isa::Sse2, Isa::Avx2].iter().copied() {
+            let supported = match Isa::detect() {
+                Some(detected) => detected.lanes() >= isa.lanes(),
+                None => false,
+            };
+            if !supported {
+                continue;
+            }
+            if let Some(profile) = Profile::new(query, &strategy, &f, isa) {
+                if let Some(score) = profile.local_score(target) {
+                    assert_eq!(score, expected.local_score(), "{:?}", isa);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn proteins_work() {
+        let alphabet = b"ARNDCQEGHILKMFPSTWYV";
+        for seed in 0..16 {
+            let query = sequence(20 + (seed as usize) * 13, seed, alphabet);
+            let target = sequence(150 - (seed as usize) * 7, seed + 100, alphabet);
+            let strategy = SmithWaterman::affine(0, 0, -10, -1);
+            assert_matches_scalar(&query, &target, strategy, |x, y| {
+                BLOSUM62.score::<isize, _>(x, y)
+            });
+        }
+    }
+
+    #[test]
+    fn linear_gaps_work() {
+        for seed in 0..16 {
+            let query = sequence(1 + (seed as usize) * 11, seed, b"ACGT");
+            let target = sequence(120, seed + 100, b"ACGT");
+            let strategy = SmithWaterman::new(2, -1, -1, -1);
+            assert_matches_scalar(&query, &target, strategy, |x, y| x == y);
+        }
+    }
+
+    #[test]
+    fn saturation_falls_back() {
+        let query = sequence(500, 1, b"ACGT");
+        let strategy = SmithWaterman::new(100, -1, -1, -1);
+        let striped = StripedSmithWaterman::new(&query, strategy, |x: u8, y: u8| x == y);
+        assert_eq!(striped.local_score(&query), 50_000);
+    }
+}
@@ -0,0 +1,180 @@
+use std::arch::x86_64::*;
+use std::mem;
+
+use super::Profile;
+
+// The operations required by the striped kernel, on vectors of `i16` lanes.
+trait Vector: Copy {
+    const LANES: usize;
+
+    unsafe fn splat(value: i16) -> Self;
+    unsafe fn load(scores: &[i16]) -> Self;
+    unsafe fn adds(self, other: Self) -> Self;
+    unsafe fn max(self, other: Self) -> Self;
+    // Shifts all lanes up by one, filling the first lane with `first`.
+    unsafe fn shift(self, first: i16) -> Self;
+    unsafe fn any_gt(self, other: Self) -> bool;
+    unsafe fn horizontal_max(self) -> i16;
+}
+
+#[derive(Copy, Clone)]
+struct Sse2(__m128i);
+
+impl Vector for Sse2 {
+    const LANES: usize = 8;
+
+    #[inline(always)]
+    unsafe fn splat(value: i16) -> Self {
+        Sse2(_mm_set1_epi16(value))
+    }
+
+    #[inline(always)]
+    unsafe fn load(scores: &[i16]) -> Self {
+        debug_assert!(scores.len() >= Self::LANES);
+        Sse2(_mm_loadu_si128(scores.as_ptr() as *const __m128i))
+    }
+
+    #[inline(always)]
+    unsafe fn adds(self, other: Self) -> Self {
+        Sse2(_mm_adds_epi16(self.0, other.0))
+    }
+
+    #[inline(always)]
+    unsafe fn max(self, other: Self) -> Self {
+        Sse2(_mm_max_epi16(self.0, other.0))
+    }
+
+    #[inline(always)]
+    unsafe fn shift(self, first: i16) -> Self {
+        let shifted = _mm_slli_si128(self.0, 2);
+        Sse2(_mm_insert_epi16(shifted, first as i32, 0))
+    }
+
+    #[inline(always)]
+    unsafe fn any_gt(self, other: Self) -> bool {
+        _mm_movemask_epi8(_mm_cmpgt_epi16(self.0, other.0)) != 0
+    }
+
+    #[inline(always)]
+    unsafe fn horizontal_max(self) -> i16 {
+        let lanes: [i16; 8] = mem::transmute(self.0);
+        lanes.iter().copied().max().unwrap()
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Avx2(__m256i);
+
+impl Vector for Avx2 {
+    const LANES: usize = 16;
+
+    #[inline(always)]
+    unsafe fn splat(value: i16) -> Self {
+        Avx2(_mm256_set1_epi16(value))
+    }
+
+    #[inline(always)]
+    unsafe fn load(scores: &[i16]) -> Self {
+        debug_assert!(scores.len() >= Self::LANES);
+        Avx2(_mm256_loadu_si256(scores.as_ptr() as *const __m256i))
+    }
+
+    #[inline(always)]
+    unsafe fn adds(self, other: Self) -> Self {
+        Avx2(_mm256_adds_epi16(self.0, other.0))
+    }
+
+    #[inline(always)]
+    unsafe fn max(self, other: Self) -> Self {
+        Avx2(_mm256_max_epi16(self.0, other.0))
+    }
+
+    #[inline(always)]
+    unsafe fn shift(self, first: i16) -> Self {
+        // Shifting across the two 128-bit halves requires moving
+        // the lower half into the upper one first:
+        let carry = _mm256_permute2x128_si256(self.0, self.0, 0x08);
+        let shifted = _mm256_alignr_epi8(self.0, carry, 14);
+        Avx2(_mm256_insert_epi16(shifted, first, 0))
+    }
+
+    #[inline(always)]
+    unsafe fn any_gt(self, other: Self) -> bool {
+        _mm256_movemask_epi8(_mm256_cmpgt_epi16(self.0, other.0)) != 0
+    }
+
+    #[inline(always)]
+    unsafe fn horizontal_max(self) -> i16 {
+        let lanes: [i16; 16] = mem::transmute(self.0);
+        lanes.iter().copied().max().unwrap()
+    }
+}
+
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn local_score_sse2(profile: &Profile, target: &[u8]) -> i16 {
+    local_score::<Sse2>(profile, target)
+}
+
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn local_score_avx2(profile: &Profile, target: &[u8]) -> i16 {
+    local_score::<Avx2>(profile, target)
+}
+
+// Farrar's striped Smith-Waterman, with the query `x` laid out across
+// the vectors' lanes and the target `y` being processed one symbol at a time.
+//
+// Moving along `x` corresponds to a deletion (`F`), moving along `y`
+// to an insertion (`E`), with unreachable states being `i16::MIN`.
+#[inline(always)]
+unsafe fn local_score<V: Vector>(profile: &Profile, target: &[u8]) -> i16 {
+    debug_assert_eq!(profile.lanes, V::LANES);
+    let segments = profile.segments;
+    let stride = segments * V::LANES;
+
+    let zero = V::splat(0);
+    let insert_extend = V::splat(profile.insert_extend);
+    let insert_open = V::splat(profile.insert_open);
+    let delete_extend = V::splat(profile.delete_extend);
+    let delete_open = V::splat(profile.delete_open);
+
+    let mut h_load = vec![zero; segments];
+    let mut h_store = vec![zero; segments];
+    let mut e = vec![V::splat(i16::MIN); segments];
+    let mut best = zero;
+
+    for &symbol in target {
+        let scores = &profile.scores[(symbol as usize) * stride..][..stride];
+
+        let mut f = V::splat(i16::MIN);
+        let mut h = h_store[segments - 1].shift(0);
+        mem::swap(&mut h_load, &mut h_store);
+
+        for segment in 0..segments {
+            h = h.adds(V::load(&scores[segment * V::LANES..]));
+            h = h.max(e[segment]).max(f).max(zero);
+            best = best.max(h);
+            h_store[segment] = h;
+            e[segment] = e[segment].adds(insert_extend).max(h.adds(insert_open));
+            f = f.adds(delete_extend).max(h.adds(delete_open));
+            h = h_load[segment];
+        }
+
+        // Lazy-F loop, propagating deletions across lane boundaries,
+        // for at most one pass per lane:
+        'lazy: for _ in 0..V::LANES {
+            f = f.shift(i16::MIN);
+            for segment in 0..segments {
+                let h = h_store[segment].max(f);
+                best = best.max(h);
+                h_store[segment] = h;
+                e[segment] = e[segment].max(h.adds(insert_open));
+                if !f.any_gt(h.adds(delete_open)) {
+                    break 'lazy;
+                }
+                f = f.adds(delete_extend);
+            }
+        }
+    }
+
+    best.horizontal_max()
+}
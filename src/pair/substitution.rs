@@ -0,0 +1,26 @@
+use crate::pair::score::Score;
+use crate::pair::strategy::Strategy;
+
+/// The result of comparing two symbols, as returned by an alignment's scoring closure.
+///
+/// A `bool` picks between the strategy's match and mismatch scores,
+/// while a score (e.g. from a `SubstitutionMatrix`) is used as is.
+pub trait Substitution<N> {
+    fn score<S: Strategy<Score = N>>(self, strategy: &S) -> N;
+}
+
+impl<N: Score> Substitution<N> for bool {
+    fn score<S: Strategy<Score = N>>(self, strategy: &S) -> N {
+        if self {
+            strategy.match_score()
+        } else {
+            strategy.mismatch_score()
+        }
+    }
+}
+
+impl<N: Score> Substitution<N> for N {
+    fn score<S: Strategy<Score = N>>(self, _strategy: &S) -> N {
+        self
+    }
+}
@@ -0,0 +1,91 @@
+use super::SubstitutionMatrix;
+
+/// The BLOSUM45 matrix, suited for distantly related protein sequences.
+#[rustfmt::skip]
+pub const BLOSUM45: SubstitutionMatrix = SubstitutionMatrix::new([
+    //    A   R   N   D   C   Q   E   G   H   I   L   K   M   F   P   S   T   W   Y   V   B   Z   X   *
+    [  5,  -2,  -1,  -2,  -1,  -1,  -1,   0,  -2,  -1,  -1,  -1,  -1,  -2,  -1,   1,   0,  -2,  -2,   0,  -1,  -1,   0,  -5], // A
+    [ -2,   7,   0,  -1,  -3,   1,   0,  -2,   0,  -3,  -2,   3,  -1,  -2,  -2,  -1,  -1,  -2,  -1,  -2,  -1,   0,  -1,  -5], // R
+    [ -1,   0,   6,   2,  -2,   0,   0,   0,   1,  -2,  -3,   0,  -2,  -2,  -2,   1,   0,  -4,  -2,  -3,   4,   0,  -1,  -5], // N
+    [ -2,  -1,   2,   7,  -3,   0,   2,  -1,   0,  -4,  -3,   0,  -3,  -4,  -1,   0,  -1,  -4,  -2,  -3,   5,   1,  -1,  -5], // D
+    [ -1,  -3,  -2,  -3,  12,  -3,  -3,  -3,  -3,  -3,  -2,  -3,  -2,  -2,  -4,  -1,  -1,  -5,  -3,  -1,  -2,  -3,  -2,  -5], // C
+    [ -1,   1,   0,   0,  -3,   6,   2,  -2,   1,  -2,  -2,   1,   0,  -4,  -1,   0,  -1,  -2,  -1,  -3,   0,   4,  -1,  -5], // Q
+    [ -1,   0,   0,   2,  -3,   2,   6,  -2,   0,  -3,  -2,   1,  -2,  -3,   0,   0,  -1,  -3,  -2,  -3,   1,   4,  -1,  -5], // E
+    [  0,  -2,   0,  -1,  -3,  -2,  -2,   7,  -2,  -4,  -3,  -2,  -2,  -3,  -2,   0,  -2,  -2,  -3,  -3,  -1,  -2,  -1,  -5], // G
+    [ -2,   0,   1,   0,  -3,   1,   0,  -2,  10,  -3,  -2,  -1,   0,  -2,  -2,  -1,  -2,  -3,   2,  -3,   0,   0,  -1,  -5], // H
+    [ -1,  -3,  -2,  -4,  -3,  -2,  -3,  -4,  -3,   5,   2,  -3,   2,   0,  -2,  -2,  -1,  -2,   0,   3,  -3,  -3,  -1,  -5], // I
+    [ -1,  -2,  -3,  -3,  -2,  -2,  -2,  -3,  -2,   2,   5,  -3,   2,   1,  -3,  -3,  -1,  -2,   0,   1,  -3,  -2,  -1,  -5], // L
+    [ -1,   3,   0,   0,  -3,   1,   1,  -2,  -1,  -3,  -3,   5,  -1,  -3,  -1,  -1,  -1,  -2,  -1,  -2,   0,   1,  -1,  -5], // K
+    [ -1,  -1,  -2,  -3,  -2,   0,  -2,  -2,   0,   2,   2,  -1,   6,   0,  -2,  -2,  -1,  -2,   0,   1,  -2,  -1,  -1,  -5], // M
+    [ -2,  -2,  -2,  -4,  -2,  -4,  -3,  -3,  -2,   0,   1,  -3,   0,   8,  -3,  -2,  -1,   1,   3,   0,  -3,  -3,  -1,  -5], // F
+    [ -1,  -2,  -2,  -1,  -4,  -1,   0,  -2,  -2,  -2,  -3,  -1,  -2,  -3,   9,  -1,  -1,  -3,  -3,  -3,  -2,  -1,  -1,  -5], // P
+    [  1,  -1,   1,   0,  -1,   0,   0,   0,  -1,  -2,  -3,  -1,  -2,  -2,  -1,   4,   2,  -4,  -2,  -1,   0,   0,   0,  -5], // S
+    [  0,  -1,   0,  -1,  -1,  -1,  -1,  -2,  -2,  -1,  -1,  -1,  -1,  -1,  -1,   2,   5,  -3,  -1,   0,   0,  -1,   0,  -5], // T
+    [ -2,  -2,  -4,  -4,  -5,  -2,  -3,  -2,  -3,  -2,  -2,  -2,  -2,   1,  -3,  -4,  -3,  15,   3,  -3,  -4,  -2,  -2,  -5], // W
+    [ -2,  -1,  -2,  -2,  -3,  -1,  -2,  -3,   2,   0,   0,  -1,   0,   3,  -3,  -2,  -1,   3,   8,  -1,  -2,  -2,  -1,  -5], // Y
+    [  0,  -2,  -3,  -3,  -1,  -3,  -3,  -3,  -3,   3,   1,  -2,   1,   0,  -3,  -1,   0,  -3,  -1,   5,  -3,  -3,  -1,  -5], // V
+    [ -1,  -1,   4,   5,  -2,   0,   1,  -1,   0,  -3,  -3,   0,  -2,  -3,  -2,   0,   0,  -4,  -2,  -3,   4,   2,  -1,  -5], // B
+    [ -1,   0,   0,   1,  -3,   4,   4,  -2,   0,  -3,  -2,   1,  -1,  -3,  -1,   0,  -1,  -2,  -2,  -3,   2,   4,  -1,  -5], // Z
+    [  0,  -1,  -1,  -1,  -2,  -1,  -1,  -1,  -1,  -1,  -1,  -1,  -1,  -1,  -1,   0,   0,  -2,  -1,  -1,  -1,  -1,  -1,  -5], // X
+    [ -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,  -5,   1], // *
+]);
+
+/// The BLOSUM62 matrix, the common default for protein sequences.
+#[rustfmt::skip]
+pub const BLOSUM62: SubstitutionMatrix = SubstitutionMatrix::new([
+    //    A   R   N   D   C   Q   E   G   H   I   L   K   M   F   P   S   T   W   Y   V   B   Z   X   *
+    [  4,  -1,  -2,  -2,   0,  -1,  -1,   0,  -2,  -1,  -1,  -1,  -1,  -2,  -1,   1,   0,  -3,  -2,   0,  -2,  -1,   0,  -4], // A
+    [ -1,   5,   0,  -2,  -3,   1,   0,  -2,   0,  -3,  -2,   2,  -1,  -3,  -2,  -1,  -1,  -3,  -2,  -3,  -1,   0,  -1,  -4], // R
+    [ -2,   0,   6,   1,  -3,   0,   0,   0,   1,  -3,  -3,   0,  -2,  -3,  -2,   1,   0,  -4,  -2,  -3,   3,   0,  -1,  -4], // N
+    [ -2,  -2,   1,   6,  -3,   0,   2,  -1,  -1,  -3,  -4,  -1,  -3,  -3,  -1,   0,  -1,  -4,  -3,  -3,   4,   1,  -1,  -4], // D
+    [  0,  -3,  -3,  -3,   9,  -3,  -4,  -3,  -3,  -1,  -1,  -3,  -1,  -2,  -3,  -1,  -1,  -2,  -2,  -1,  -3,  -3,  -2,  -4], // C
+    [ -1,   1,   0,   0,  -3,   5,   2,  -2,   0,  -3,  -2,   1,   0,  -3,  -1,   0,  -1,  -2,  -1,  -2,   0,   3,  -1,  -4], // Q
+    [ -1,   0,   0,   2,  -4,   2,   5,  -2,   0,  -3,  -3,   1,  -2,  -3,  -1,   0,  -1,  -3,  -2,  -2,   1,   4,  -1,  -4], // E
+    [  0,  -2,   0,  -1,  -3,  -2,  -2,   6,  -2,  -4,  -4,  -2,  -3,  -3,  -2,   0,  -2,  -2,  -3,  -3,  -1,  -2,  -1,  -4], // G
+    [ -2,   0,   1,  -1,  -3,   0,   0,  -2,   8,  -3,  -3,  -1,  -2,  -1,  -2,  -1,  -2,  -2,   2,  -3,   0,   0,  -1,  -4], // H
+    [ -1,  -3,  -3,  -3,  -1,  -3,  -3,  -4,  -3,   4,   2,  -3,   1,   0,  -3,  -2,  -1,  -3,  -1,   3,  -3,  -3,  -1,  -4], // I
+    [ -1,  -2,  -3,  -4,  -1,  -2,  -3,  -4,  -3,   2,   4,  -2,   2,   0,  -3,  -2,  -1,  -2,  -1,   1,  -4,  -3,  -1,  -4], // L
+    [ -1,   2,   0,  -1,  -3,   1,   1,  -2,  -1,  -3,  -2,   5,  -1,  -3,  -1,   0,  -1,  -3,  -2,  -2,   0,   1,  -1,  -4], // K
+    [ -1,  -1,  -2,  -3,  -1,   0,  -2,  -3,  -2,   1,   2,  -1,   5,   0,  -2,  -1,  -1,  -1,  -1,   1,  -3,  -1,  -1,  -4], // M
+    [ -2,  -3,  -3,  -3,  -2,  -3,  -3,  -3,  -1,   0,   0,  -3,   0,   6,  -4,  -2,  -2,   1,   3,  -1,  -3,  -3,  -1,  -4], // F
+    [ -1,  -2,  -2,  -1,  -3,  -1,  -1,  -2,  -2,  -3,  -3,  -1,  -2,  -4,   7,  -1,  -1,  -4,  -3,  -2,  -2,  -1,  -2,  -4], // P
+    [  1,  -1,   1,   0,  -1,   0,   0,   0,  -1,  -2,  -2,   0,  -1,  -2,  -1,   4,   1,  -3,  -2,  -2,   0,   0,   0,  -4], // S
+    [  0,  -1,   0,  -1,  -1,  -1,  -1,  -2,  -2,  -1,  -1,  -1,  -1,  -2,  -1,   1,   5,  -2,  -2,   0,  -1,  -1,   0,  -4], // T
+    [ -3,  -3,  -4,  -4,  -2,  -2,  -3,  -2,  -2,  -3,  -2,  -3,  -1,   1,  -4,  -3,  -2,  11,   2,  -3,  -4,  -3,  -2,  -4], // W
+    [ -2,  -2,  -2,  -3,  -2,  -1,  -2,  -3,   2,  -1,  -1,  -2,  -1,   3,  -3,  -2,  -2,   2,   7,  -1,  -3,  -2,  -1,  -4], // Y
+    [  0,  -3,  -3,  -3,  -1,  -2,  -2,  -3,  -3,   3,   1,  -2,   1,  -1,  -2,  -2,   0,  -3,  -1,   4,  -3,  -2,  -1,  -4], // V
+    [ -2,  -1,   3,   4,  -3,   0,   1,  -1,   0,  -3,  -4,   0,  -3,  -3,  -2,   0,  -1,  -4,  -3,  -3,   4,   1,  -1,  -4], // B
+    [ -1,   0,   0,   1,  -3,   3,   4,  -2,   0,  -3,  -3,   1,  -1,  -3,  -1,   0,  -1,  -3,  -2,  -2,   1,   4,  -1,  -4], // Z
+    [  0,  -1,  -1,  -1,  -2,  -1,  -1,  -1,  -1,  -1,  -1,  -1,  -1,  -1,  -2,   0,   0,  -2,  -1,  -1,  -1,  -1,  -1,  -4], // X
+    [ -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,  -4,   1], // *
+]);
+
+/// The BLOSUM80 matrix, suited for closely related protein sequences.
+#[rustfmt::skip]
+pub const BLOSUM80: SubstitutionMatrix = SubstitutionMatrix::new([
+    //    A   R   N   D   C   Q   E   G   H   I   L   K   M   F   P   S   T   W   Y   V   B   Z   X   *
+    [  7,  -3,  -3,  -3,  -1,  -2,  -2,   0,  -3,  -3,  -3,  -1,  -2,  -4,  -1,   2,   0,  -5,  -4,  -1,  -3,  -2,  -1,  -8], // A
+    [ -3,   9,  -1,  -3,  -6,   1,  -1,  -4,   0,  -5,  -4,   3,  -3,  -5,  -3,  -2,  -2,  -5,  -4,  -4,  -2,   0,  -2,  -8], // R
+    [ -3,  -1,   9,   2,  -5,   0,  -1,  -1,   1,  -6,  -6,   0,  -4,  -6,  -4,   1,   0,  -7,  -4,  -5,   5,  -1,  -2,  -8], // N
+    [ -3,  -3,   2,  10,  -7,  -1,   2,  -3,  -2,  -7,  -7,  -2,  -6,  -6,  -3,  -1,  -2,  -8,  -6,  -6,   6,   1,  -3,  -8], // D
+    [ -1,  -6,  -5,  -7,  13,  -5,  -7,  -6,  -7,  -2,  -3,  -6,  -3,  -4,  -6,  -2,  -2,  -5,  -5,  -2,  -6,  -7,  -4,  -8], // C
+    [ -2,   1,   0,  -1,  -5,   9,   3,  -4,   1,  -5,  -4,   2,  -1,  -5,  -3,  -1,  -1,  -4,  -3,  -4,  -1,   5,  -2,  -8], // Q
+    [ -2,  -1,  -1,   2,  -7,   3,   8,  -4,   0,  -6,  -6,   1,  -4,  -6,  -2,  -1,  -2,  -6,  -5,  -4,   1,   6,  -2,  -8], // E
+    [  0,  -4,  -1,  -3,  -6,  -4,  -4,   9,  -4,  -7,  -7,  -3,  -5,  -6,  -5,  -1,  -3,  -6,  -6,  -6,  -2,  -4,  -3,  -8], // G
+    [ -3,   0,   1,  -2,  -7,   1,   0,  -4,  12,  -6,  -5,  -1,  -4,  -2,  -4,  -2,  -3,  -4,   3,  -5,  -1,   0,  -2,  -8], // H
+    [ -3,  -5,  -6,  -7,  -2,  -5,  -6,  -7,  -6,   7,   2,  -5,   2,  -1,  -5,  -4,  -2,  -5,  -3,   4,  -6,  -6,  -2,  -8], // I
+    [ -3,  -4,  -6,  -7,  -3,  -4,  -6,  -7,  -5,   2,   6,  -4,   3,   0,  -5,  -4,  -3,  -4,  -2,   1,  -7,  -5,  -2,  -8], // L
+    [ -1,   3,   0,  -2,  -6,   2,   1,  -3,  -1,  -5,  -4,   8,  -3,  -5,  -2,  -1,  -1,  -6,  -4,  -4,  -1,   1,  -2,  -8], // K
+    [ -2,  -3,  -4,  -6,  -3,  -1,  -4,  -5,  -4,   2,   3,  -3,   9,   0,  -4,  -3,  -1,  -3,  -3,   1,  -5,  -3,  -2,  -8], // M
+    [ -4,  -5,  -6,  -6,  -4,  -5,  -6,  -6,  -2,  -1,   0,  -5,   0,  10,  -6,  -4,  -4,   0,   4,  -2,  -6,  -6,  -3,  -8], // F
+    [ -1,  -3,  -4,  -3,  -6,  -3,  -2,  -5,  -4,  -5,  -5,  -2,  -4,  -6,  12,  -2,  -3,  -7,  -6,  -4,  -4,  -2,  -3,  -8], // P
+    [  2,  -2,   1,  -1,  -2,  -1,  -1,  -1,  -2,  -4,  -4,  -1,  -3,  -4,  -2,   7,   2,  -6,  -3,  -3,   0,  -1,  -1,  -8], // S
+    [  0,  -2,   0,  -2,  -2,  -1,  -2,  -3,  -3,  -2,  -3,  -1,  -1,  -4,  -3,   2,   8,  -5,  -3,   0,  -1,  -2,  -1,  -8], // T
+    [ -5,  -5,  -7,  -8,  -5,  -4,  -6,  -6,  -4,  -5,  -4,  -6,  -3,   0,  -7,  -6,  -5,  16,   3,  -5,  -8,  -5,  -5,  -8], // W
+    [ -4,  -4,  -4,  -6,  -5,  -3,  -5,  -6,   3,  -3,  -2,  -4,  -3,   4,  -6,  -3,  -3,   3,  11,  -3,  -5,  -4,  -3,  -8], // Y
+    [ -1,  -4,  -5,  -6,  -2,  -4,  -4,  -6,  -5,   4,   1,  -4,   1,  -2,  -4,  -3,   0,  -5,  -3,   7,  -6,  -4,  -2,  -8], // V
+    [ -3,  -2,   5,   6,  -6,  -1,   1,  -2,  -1,  -6,  -7,  -1,  -5,  -6,  -4,   0,  -1,  -8,  -5,  -6,   6,   0,  -3,  -8], // B
+    [ -2,   0,  -1,   1,  -7,   5,   6,  -4,   0,  -6,  -5,   1,  -3,  -6,  -2,  -1,  -2,  -5,  -4,  -4,   0,   6,  -1,  -8], // Z
+    [ -1,  -2,  -2,  -3,  -4,  -2,  -2,  -3,  -2,  -2,  -2,  -2,  -2,  -3,  -3,  -1,  -1,  -5,  -3,  -2,  -3,  -1,  -2,  -8], // X
+    [ -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,  -8,   1], // *
+]);
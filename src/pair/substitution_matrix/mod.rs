@@ -0,0 +1,112 @@
+mod blosum;
+mod pam;
+
+use crate::pair::score::Score;
+
+pub use self::blosum::{BLOSUM45, BLOSUM62, BLOSUM80};
+pub use self::pam::{PAM250, PAM30, PAM70};
+
+const ALPHABET: &[u8; 24] = b"ARNDCQEGHILKMFPSTWYVBZX*";
+
+// Index of 'X', used for any symbol not contained in `ALPHABET`.
+const UNKNOWN: u8 = 22;
+
+const INDICES: [u8; 256] = {
+    let mut indices = [UNKNOWN; 256];
+    let mut i = 0;
+    while i < ALPHABET.len() {
+        let symbol = ALPHABET[i];
+        indices[symbol as usize] = i as u8;
+        indices[symbol.to_ascii_lowercase() as usize] = i as u8;
+        i += 1;
+    }
+    indices
+};
+
+/// A symmetric amino acid substitution matrix (such as BLOSUM or PAM).
+///
+/// Symbols are looked up case-insensitively,
+/// with unknown symbols being scored like `X`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubstitutionMatrix {
+    scores: [[i8; 24]; 24],
+}
+
+impl SubstitutionMatrix {
+    /// Creates a matrix from scores indexed in the order of `SubstitutionMatrix::alphabet()`.
+    pub const fn new(scores: [[i8; 24]; 24]) -> SubstitutionMatrix {
+        SubstitutionMatrix { scores }
+    }
+
+    pub fn alphabet() -> &'static [u8] {
+        ALPHABET
+    }
+
+    /// The score of substituting `x` with `y`, in any score type that can represent it.
+    pub fn score<N, T>(&self, x: T, y: T) -> N
+    where
+        N: Score + From<i8>,
+        T: Into<u32>,
+    {
+        let x = Self::index(x.into());
+        let y = Self::index(y.into());
+        N::from(self.scores[x][y])
+    }
+
+    fn index(symbol: u32) -> usize {
+        match u8::try_from(symbol) {
+            Ok(byte) => INDICES[byte as usize] as usize,
+            Err(_) => UNKNOWN as usize,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrices() -> Vec<SubstitutionMatrix> {
+        vec![BLOSUM45, BLOSUM62, BLOSUM80, PAM30, PAM70, PAM250]
+    }
+
+    #[test]
+    fn symmetric() {
+        for matrix in matrices() {
+            for &x in SubstitutionMatrix::alphabet() {
+                for &y in SubstitutionMatrix::alphabet() {
+                    assert_eq!(matrix.score::<i16, _>(x, y), matrix.score::<i16, _>(y, x));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn score_works() {
+        assert_eq!(BLOSUM62.score::<isize, _>(b'A', b'A'), 4);
+        assert_eq!(BLOSUM62.score::<isize, _>(b'W', b'W'), 11);
+        assert_eq!(BLOSUM62.score::<isize, _>(b'H', b'A'), -2);
+        assert_eq!(BLOSUM45.score::<isize, _>(b'C', b'C'), 12);
+        assert_eq!(BLOSUM80.score::<isize, _>(b'P', b'P'), 12);
+        assert_eq!(PAM30.score::<isize, _>(b'W', b'E'), -17);
+        assert_eq!(PAM70.score::<isize, _>(b'M', b'M'), 10);
+        assert_eq!(PAM250.score::<isize, _>(b'W', b'W'), 17);
+        assert_eq!(BLOSUM62.score::<i16, _>(b'H', b'A'), -2);
+        assert_eq!(BLOSUM62.score::<f32, _>(b'H', b'A'), -2.0);
+    }
+
+    #[test]
+    fn lookup_works() {
+        assert_eq!(
+            BLOSUM62.score::<isize, _>('a', 'R'),
+            BLOSUM62.score::<isize, _>('A', 'R')
+        );
+        assert_eq!(
+            BLOSUM62.score::<isize, _>(b'J', b'A'),
+            BLOSUM62.score::<isize, _>(b'X', b'A')
+        );
+        assert_eq!(
+            BLOSUM62.score::<isize, _>('λ', 'A'),
+            BLOSUM62.score::<isize, _>('X', 'A')
+        );
+    }
+}
@@ -0,0 +1,14 @@
+// Helpers shared by the tests of several modules.
+
+/// A pseudo-random sequence of `len` symbols from `alphabet`, deterministic for a given `seed`.
+pub(crate) fn sequence(len: usize, seed: u64, alphabet: &[u8]) -> Vec<u8> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            alphabet[(state >> 33) as usize % alphabet.len()]
+        })
+        .collect()
+}